@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use crate::state::Resolution;
+use crate::errors::IpredictError;
+
+// Layout of a native ed25519 program instruction carrying one signature
+const SIGNATURE_COUNT_OFFSET: usize = 0;
+const OFFSETS_START: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+/// Instruction index meaning "this same instruction" in the signature offsets
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// The message an attester signs to resolve a wager: program id, wager key,
+/// the resolution's Borsh encoding and the attestation's unix timestamp
+pub fn attestation_message(wager: &Pubkey, resolution: Resolution, timestamp: i64) -> Result<Vec<u8>> {
+    let mut message = Vec::with_capacity(32 + 32 + 1 + 8);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(wager.as_ref());
+    resolution.serialize(&mut message)?;
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    Ok(message)
+}
+
+/// Check that the instruction just before this one is a native ed25519
+/// program verification of `message` signed by `attester`. The runtime has
+/// already rejected the transaction if that signature was invalid.
+pub fn verify_preceding_ed25519(instructions: &AccountInfo, attester: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    if current == 0 {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    
+    let data = &ix.data;
+    if data.len() < OFFSETS_START + OFFSETS_LEN || data[SIGNATURE_COUNT_OFFSET] != 1 {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    let offset = |i: usize| -> u16 {
+        let at = OFFSETS_START + i * 2;
+        u16::from_le_bytes([data[at], data[at + 1]])
+    };
+    let signature_offset = offset(0) as usize;
+    let signature_ix = offset(1);
+    let pubkey_offset = offset(2) as usize;
+    let pubkey_ix = offset(3);
+    let message_offset = offset(4) as usize;
+    let message_len = offset(5) as usize;
+    let message_ix = offset(6);
+    
+    // Everything must be read from the ed25519 instruction itself, so it is
+    // what the runtime actually verified
+    if signature_ix != THIS_INSTRUCTION || pubkey_ix != THIS_INSTRUCTION || message_ix != THIS_INSTRUCTION {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    if data.len() < signature_offset + SIGNATURE_LEN {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    
+    let signed_pubkey = data.get(pubkey_offset..pubkey_offset + PUBKEY_LEN);
+    let signed_message = data.get(message_offset..message_offset + message_len);
+    if signed_pubkey != Some(attester.as_ref()) || signed_message != Some(message) {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::sysvar;
+    use anchor_lang::solana_program::sysvar::instructions::{construct_instructions_data, store_current_index, BorrowedInstruction};
+    
+    // Where the native program's own instruction builder puts each part
+    const PUBKEY_OFFSET: usize = OFFSETS_START + OFFSETS_LEN;
+    const SIGNATURE_OFFSET: usize = PUBKEY_OFFSET + PUBKEY_LEN;
+    const MESSAGE_OFFSET: usize = SIGNATURE_OFFSET + SIGNATURE_LEN;
+    
+    fn ed25519_data(pubkey: &Pubkey, message: &[u8]) -> Vec<u8> {
+        let offsets = [
+            SIGNATURE_OFFSET as u16,
+            THIS_INSTRUCTION,
+            PUBKEY_OFFSET as u16,
+            THIS_INSTRUCTION,
+            MESSAGE_OFFSET as u16,
+            message.len() as u16,
+            THIS_INSTRUCTION,
+        ];
+        let mut data = vec![1, 0];
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(&[7; SIGNATURE_LEN]);
+        data.extend_from_slice(message);
+        data
+    }
+    
+    fn set_offset(data: &mut [u8], i: usize, value: u16) {
+        let at = OFFSETS_START + i * 2;
+        data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+    }
+    
+    /// Run the check from the instruction at `current`, with `instructions`
+    /// as the transaction's `(program_id, data)` list
+    fn verify(instructions: &[(Pubkey, Vec<u8>)], current: u16, attester: &Pubkey, message: &[u8]) -> Result<()> {
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction { program_id, accounts: Vec::new(), data })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        store_current_index(&mut data, current);
+        
+        let key = sysvar::instructions::ID;
+        let owner = sysvar::ID;
+        let mut lamports = 0;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        verify_preceding_ed25519(&account, attester, message)
+    }
+    
+    fn verify_data(data: Vec<u8>, attester: &Pubkey, message: &[u8]) -> Result<()> {
+        verify(&[(ed25519_program::ID, data), (crate::ID, Vec::new())], 1, attester, message)
+    }
+    
+    fn setup() -> (Pubkey, Vec<u8>) {
+        let wager = Pubkey::new_unique();
+        (Pubkey::new_unique(), attestation_message(&wager, Resolution::YesWon, 1_700_000_000).unwrap())
+    }
+    
+    #[test]
+    fn accepts_preceding_signature_by_attester() {
+        let (attester, message) = setup();
+        assert!(verify_data(ed25519_data(&attester, &message), &attester, &message).is_ok());
+    }
+    
+    #[test]
+    fn rejects_other_signer() {
+        let (attester, message) = setup();
+        let data = ed25519_data(&Pubkey::new_unique(), &message);
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+    }
+    
+    #[test]
+    fn rejects_other_message() {
+        let (attester, message) = setup();
+        let wager = Pubkey::new_unique();
+        let signed = attestation_message(&wager, Resolution::NoWon, 1_700_000_000).unwrap();
+        let data = ed25519_data(&attester, &signed);
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+        
+        // A signed prefix of the expected message is not enough
+        let data = ed25519_data(&attester, &message[..message.len() - 1]);
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+    }
+    
+    #[test]
+    fn rejects_offsets_into_other_instructions() {
+        let (attester, message) = setup();
+        // Signature, pubkey and message instruction indexes
+        for i in [1, 3, 6] {
+            let mut data = ed25519_data(&attester, &message);
+            set_offset(&mut data, i, 0);
+            assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+        }
+    }
+    
+    #[test]
+    fn rejects_truncated_instruction() {
+        let (attester, message) = setup();
+        let full = ed25519_data(&attester, &message);
+        for len in [0, OFFSETS_START + OFFSETS_LEN - 1, SIGNATURE_OFFSET, MESSAGE_OFFSET - 1, full.len() - 1] {
+            let data = full[..len].to_vec();
+            assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+        }
+        
+        // Offsets pointing past the end of the data
+        let mut data = full.clone();
+        set_offset(&mut data, 0, full.len() as u16);
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+        let mut data = full;
+        set_offset(&mut data, 5, u16::MAX);
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+    }
+    
+    #[test]
+    fn rejects_missing_or_foreign_verification() {
+        let (attester, message) = setup();
+        let data = ed25519_data(&attester, &message);
+        
+        // Nothing precedes the resolving instruction
+        let err = verify(&[(crate::ID, Vec::new()), (ed25519_program::ID, data.clone())], 0, &attester, &message);
+        assert_eq!(err.unwrap_err(), IpredictError::InvalidAttestation.into());
+        
+        // The same bytes sent to some other program were never verified
+        let err = verify(&[(Pubkey::new_unique(), data.clone()), (crate::ID, Vec::new())], 1, &attester, &message);
+        assert_eq!(err.unwrap_err(), IpredictError::InvalidAttestation.into());
+        
+        // Two signatures in one instruction
+        let mut data = data;
+        data[SIGNATURE_COUNT_OFFSET] = 2;
+        assert_eq!(verify_data(data, &attester, &message).unwrap_err(), IpredictError::InvalidAttestation.into());
+    }
+}
\ No newline at end of file
@@ -1,15 +1,23 @@
-use anchor_lang::prelude::*;
-
 pub const PLATFORM_SEED: &[u8] = b"platform";
 pub const WAGER_SEED: &[u8] = b"wager";
 pub const VAULT_SEED: &[u8] = b"vault";
-pub const ORDER_BOOK_SEED: &[u8] = b"order_book";
 pub const ORDER_SEED: &[u8] = b"order";
 pub const USER_POSITION_SEED: &[u8] = b"user_position";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const BID_ESCROW_SEED: &[u8] = b"bid_escrow";
+pub const OPEN_ORDERS_SEED: &[u8] = b"open_orders";
+pub const COLLATERAL_VAULT_SEED: &[u8] = b"collateral_vault";
+pub const BID_ESCROW_VAULT_SEED: &[u8] = b"bid_escrow_vault";
+pub const RESOLUTION_PROPOSAL_SEED: &[u8] = b"resolution_proposal";
+pub const COMMITTEE_SEED: &[u8] = b"committee";
+pub const DAO_TALLY_SEED: &[u8] = b"dao_tally";
+pub const DAO_VAULT_SEED: &[u8] = b"dao_vault";
+pub const VOTE_RECORD_SEED: &[u8] = b"vote_record";
+pub const RESOLUTION_RECORD_SEED: &[u8] = b"resolution_record";
 
-pub const TOKENS_PER_SOL: u64 = 100;
-pub const LAMPORTS_PER_TOKEN: u64 = 10_000_000; // 0.01 SOL
+pub const LAMPORTS_PER_TOKEN: u64 = 10_000_000; // Suggested contract size: 0.01 SOL per set
 pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const MAX_TOKEN_DECIMALS: u8 = 9;
 pub const PLATFORM_FEE_BPS: u16 = 25; // 0.25%
 pub const DEPLOYER_FEE_BPS: u16 = 25; // 0.25%
 pub const TOTAL_FEE_BPS: u16 = 50; // 0.5%
@@ -17,6 +25,25 @@ pub const BPS_DIVISOR: u64 = 10_000;
 
 pub const MAX_NAME_LENGTH: usize = 200;
 pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
+pub const MAX_URI_LENGTH: usize = 200;
+pub const MAX_RATIONALE_LENGTH: usize = 500;
 pub const MAX_ORDERS_PER_BOOK: usize = 1000;
+pub const MAX_OPEN_ORDERS: usize = 32;
+pub const MAX_COMMITTEE_MEMBERS: usize = 16;
+pub const MIN_COMMITTEE_MEMBERS: usize = 2;
+pub const MIN_COMMITTEE_THRESHOLD: u8 = 2;
+pub const ROTATION_PROPOSAL_TTL: i64 = 259_200; // 3 days
+
+pub const WAGER_CREATION_FEE: u64 = 1_000_000_000; // 1 SOL
+
+pub const DEFAULT_RESOLUTION_BOND: u64 = 1_000_000_000; // 1 SOL
+pub const DEFAULT_DISPUTE_WINDOW: i64 = 86_400; // 24 hours
+pub const DEFAULT_PROPOSER_REWARD: u64 = 100_000_000; // 0.1 SOL
+pub const DEFAULT_RESOLUTION_GRACE_PERIOD: i64 = 2_592_000; // 30 days
+
+pub const DEFAULT_DAO_VOTING_PERIOD: i64 = 259_200; // 3 days
 
-pub const WAGER_CREATION_FEE: u64 = 1_000_000_000; // 1 SOL
\ No newline at end of file
+pub const MAX_ORACLE_STALENESS: i64 = 60; // seconds
+pub const MAX_ORACLE_CONFIDENCE_BPS: u64 = 100; // 1% of price
+pub const ORACLE_FALLBACK_DELAY: i64 = 604_800; // 7 days
+pub const ATTESTATION_FALLBACK_DELAY: i64 = 604_800; // 7 days
\ No newline at end of file
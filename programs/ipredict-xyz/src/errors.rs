@@ -50,6 +50,12 @@ pub enum IpredictError {
     #[msg("Description too long")]
     DescriptionTooLong,
     
+    #[msg("URI too long")]
+    UriTooLong,
+    
+    #[msg("Rationale too long")]
+    RationaleTooLong,
+    
     #[msg("Math overflow")]
     MathOverflow,
     
@@ -61,4 +67,121 @@ pub enum IpredictError {
     
     #[msg("Invalid escrow mint")]
     InvalidEscrowMint,
+    
+    #[msg("Settlement account missing")]
+    SettlementAccountMissing,
+    
+    #[msg("Duplicate order")]
+    DuplicateOrder,
+    
+    #[msg("Invalid order book")]
+    InvalidOrderBook,
+    
+    #[msg("Post-only order would cross the book")]
+    PostOnlyWouldCross,
+    
+    #[msg("Fill-or-kill order cannot be filled in full")]
+    FillOrKillNotFilled,
+    
+    #[msg("Order expiry must be in the future")]
+    InvalidOrderExpiry,
+    
+    #[msg("Too many open orders")]
+    TooManyOpenOrders,
+    
+    #[msg("Invalid contract size")]
+    InvalidContractSize,
+    
+    #[msg("Invalid collateral vault")]
+    InvalidVault,
+    
+    #[msg("Invalid collateral account")]
+    InvalidCollateralAccount,
+    
+    #[msg("Invalid dispute window")]
+    InvalidDisputeWindow,
+    
+    #[msg("Dispute window still open")]
+    DisputeWindowOpen,
+    
+    #[msg("Dispute window closed")]
+    DisputeWindowClosed,
+    
+    #[msg("No resolution proposal pending")]
+    NoResolutionProposed,
+    
+    #[msg("Resolution not disputed")]
+    ResolutionNotDisputed,
+    
+    #[msg("Invalid oracle condition")]
+    InvalidOracleCondition,
+    
+    #[msg("Invalid oracle account")]
+    InvalidOracleAccount,
+    
+    #[msg("Wager is not resolved by an oracle")]
+    NotOracleWager,
+    
+    #[msg("Wager is resolved by a different arbitrator")]
+    InvalidArbitrator,
+    
+    #[msg("Oracle price is stale")]
+    StaleOraclePrice,
+    
+    #[msg("Oracle price is too uncertain")]
+    OracleConfidenceTooWide,
+    
+    #[msg("Invalid resolver committee")]
+    InvalidCommittee,
+    
+    #[msg("Not a committee member")]
+    NotCommitteeMember,
+    
+    #[msg("Voting deadline passed")]
+    VotingClosed,
+    
+    #[msg("DAO resolution not configured")]
+    DaoNotConfigured,
+    
+    #[msg("Votes are still locked")]
+    VotesLocked,
+    
+    #[msg("Invalid attestation")]
+    InvalidAttestation,
+    
+    #[msg("Resolution grace period not over")]
+    GracePeriodNotOver,
+    
+    #[msg("Trading halted")]
+    TradingHalted,
+    
+    #[msg("Trading not halted")]
+    TradingNotHalted,
+    
+    #[msg("Resolution record required")]
+    ResolutionRecordRequired,
+    
+    #[msg("Oracle account not owned by the Pyth program")]
+    InvalidOracleOwner,
+    
+    #[msg("Trading has already opened")]
+    TradingAlreadyOpen,
+    
+    #[msg("Does not match the pending committee rotation")]
+    RotationProposalMismatch,
+    
+    #[msg("Voting period not over")]
+    VotingNotOver,
+    
+    #[msg("DAO vote missed quorum or tied")]
+    DaoVoteUndecided,
+    
+    #[msg("Resolution record only accompanies the deciding vote")]
+    UnexpectedResolutionRecord,
+    
+    #[msg("Grace period must outlast every resolver fallback delay")]
+    GracePeriodTooShort,
+    
+    #[msg("Oracle price exponent differs from the wager's")]
+    OracleExponentMismatch,
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::state::{OrderSide, Resolution, TokenType};
+
+/// One order's side of a fill, stamped with when it happened so fills made
+/// after an early-resolved wager's `event_time` can be picked out
+#[event]
+pub struct OrderFilled {
+    pub wager: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub token_type: TokenType,
+    /// Owner of the other side: the crossing order, or the trader taking it
+    /// with `quick_buy` or `quick_sell`
+    pub counterparty: Pubkey,
+    pub quantity: u64,
+    /// Price per token this order traded at
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+/// A halted wager resolved before its resolution time. `OrderFilled` events
+/// stamped after `event_time` were made at a known outcome.
+#[event]
+pub struct WagerResolvedEarly {
+    pub wager: Pubkey,
+    pub resolution: Resolution,
+    pub event_time: i64,
+    pub halted_at: i64,
+    pub last_fill_at: i64,
+}
\ No newline at end of file
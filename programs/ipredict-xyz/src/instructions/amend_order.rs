@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{Wager, OrderBook, Order, OpenOrders, OrderSide, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct AmendOrder<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        address = wager.order_book @ IpredictError::InvalidOrderBook
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key()
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: AccountInfo<'info>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"no"],
+        bump
+    )]
+    pub no_escrow: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Change an order's price and unfilled quantity. A price change re-queues
+/// the order under a new id behind everything already resting at that price;
+/// a quantity change alone keeps its place. The amended order is not matched
+/// here, so one that now crosses waits for `match_orders`.
+pub fn amend_order(
+    ctx: Context<AmendOrder>,
+    order_id: u64,
+    new_price: u64,
+    new_quantity: u64,
+) -> Result<()> {
+    let wager = &ctx.accounts.wager;
+    let mut order_book = ctx.accounts.order_book.load_mut()?;
+    
+    // Validate inputs
+    if new_price == 0 || new_price > wager.collateral_per_set {
+        return Err(IpredictError::InvalidOrderPrice.into());
+    }
+    if new_quantity == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    
+    // Check wager is open
+    let clock = Clock::get()?;
+    if !wager.is_open(&clock) {
+        return Err(IpredictError::WagerNotOpen.into());
+    }
+    
+    let order = order_book.find_order(order_id).ok_or(IpredictError::OrderNotFound)?;
+    
+    // Verify ownership
+    if order.owner != ctx.accounts.user.key() {
+        return Err(IpredictError::Unauthorized.into());
+    }
+    
+    let side = order.side();
+    let token_type = order.token_type();
+    let remaining = order.remaining_quantity();
+    
+    // Top up or release escrow to cover the amended order exactly
+    match side {
+        OrderSide::Sell => {
+            let expected_mint = match token_type {
+                TokenType::Yes => wager.yes_mint,
+                TokenType::No => wager.no_mint,
+            };
+            if ctx.accounts.user_token_account.mint != expected_mint {
+                return Err(IpredictError::InvalidTokenMint.into());
+            }
+            
+            let (escrow_account, escrow_bump) = match token_type {
+                TokenType::Yes => (&ctx.accounts.yes_escrow, ctx.bumps.yes_escrow),
+                TokenType::No => (&ctx.accounts.no_escrow, ctx.bumps.no_escrow),
+            };
+            let token_mint = match token_type {
+                TokenType::Yes => ctx.accounts.yes_mint.to_account_info(),
+                TokenType::No => ctx.accounts.no_mint.to_account_info(),
+            };
+            
+            if new_quantity > remaining {
+                let additional = new_quantity - remaining;
+                if ctx.accounts.user_token_account.amount < additional {
+                    return Err(IpredictError::InsufficientBalance.into());
+                }
+                
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: token_mint,
+                    to: escrow_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.token_program.to_account_info();
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token_interface::transfer_checked(cpi_ctx, additional, wager.token_decimals)?;
+            } else if new_quantity < remaining {
+                settlement::release_from_escrow(
+                    &ctx.accounts.token_program.to_account_info(),
+                    escrow_account,
+                    &token_mint,
+                    &ctx.accounts.user_token_account.to_account_info(),
+                    &wager.key(),
+                    token_type,
+                    escrow_bump,
+                    wager.token_decimals,
+                    remaining - new_quantity,
+                )?;
+            }
+        }
+        OrderSide::Buy => {
+            let locked = remaining
+                .checked_mul(order.price)
+                .ok_or(IpredictError::MathOverflow)?;
+            let required = new_quantity
+                .checked_mul(new_price)
+                .ok_or(IpredictError::MathOverflow)?;
+            
+            let open_orders = &mut ctx.accounts.open_orders;
+            if required > locked {
+                open_orders.debit_sol(required - locked)?;
+            } else {
+                open_orders.credit_sol(locked - required)?;
+            }
+        }
+    }
+    
+    let tree = order_book.tree_mut(side, token_type);
+    let index = tree.find_by_id(order_id).ok_or(IpredictError::OrderNotFound)?;
+    
+    let amended_id = if new_price == order.price {
+        // Same price keeps time priority
+        let resting = tree.order_mut(index);
+        resting.quantity = resting.filled_quantity
+            .checked_add(new_quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        order_id
+    } else {
+        // A new price goes to the back of the queue under a new id
+        tree.remove(index);
+        
+        let mut amended = Order::new(
+            order_book.next_order_id,
+            order.owner,
+            side,
+            token_type,
+            new_price,
+            new_quantity,
+            clock.unix_timestamp,
+        );
+        amended.expires_at = order.expires_at;
+        amended.self_trade_behavior = order.self_trade_behavior;
+        
+        order_book.add_order(amended)?;
+        order_book.next_order_id = order_book.next_order_id
+            .checked_add(1)
+            .ok_or(IpredictError::MathOverflow)?;
+        let open_orders = &mut ctx.accounts.open_orders;
+        open_orders.remove_order(order_id);
+        open_orders.add_order(amended.id)?;
+        amended.id
+    };
+    
+    msg!(
+        "Order {} amended to {} tokens at {} lamports each (now order {})",
+        order_id,
+        new_quantity,
+        new_price,
+        amended_id
+    );
+    
+    Ok(())
+}
\ No newline at end of file
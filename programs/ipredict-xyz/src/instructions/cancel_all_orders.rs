@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Wager, OrderBook, OpenOrders, OrderSide, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct CancelAllOrders<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        address = wager.order_book @ IpredictError::InvalidOrderBook
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(
+        mut,
+        constraint = user_yes_account.owner == user.key(),
+        constraint = user_yes_account.mint == wager.yes_mint @ IpredictError::InvalidTokenMint
+    )]
+    pub user_yes_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = user_no_account.owner == user.key(),
+        constraint = user_no_account.mint == wager.no_mint @ IpredictError::InvalidTokenMint
+    )]
+    pub user_no_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: AccountInfo<'info>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"no"],
+        bump
+    )]
+    pub no_escrow: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Cancel every order the user owns, optionally limited to one side and/or
+/// one outcome
+pub fn cancel_all_orders(
+    ctx: Context<CancelAllOrders>,
+    side: Option<OrderSide>,
+    token_type: Option<TokenType>,
+) -> Result<()> {
+    let mut order_book = ctx.accounts.order_book.load_mut()?;
+    let user_key = ctx.accounts.user.key();
+    
+    let mut sol_refund = 0u64;
+    let mut yes_tokens_returned = 0u64;
+    let mut no_tokens_returned = 0u64;
+    let mut cancelled = 0u32;
+    
+    for (book_side, book_token_type) in [
+        (OrderSide::Buy, TokenType::Yes),
+        (OrderSide::Sell, TokenType::Yes),
+        (OrderSide::Buy, TokenType::No),
+        (OrderSide::Sell, TokenType::No),
+    ] {
+        if side.is_some_and(|side| side != book_side)
+            || token_type.is_some_and(|token_type| token_type != book_token_type)
+        {
+            continue;
+        }
+        
+        let tree = order_book.tree_mut(book_side, book_token_type);
+        while let Some(index) = tree.orders().iter().position(|order| order.owner == user_key) {
+            let order = tree.remove(index as u32);
+            ctx.accounts.open_orders.remove_order(order.id);
+            let remaining = order.remaining_quantity();
+            
+            match (book_side, book_token_type) {
+                (OrderSide::Buy, _) => {
+                    let refund = remaining
+                        .checked_mul(order.price)
+                        .ok_or(IpredictError::MathOverflow)?;
+                    sol_refund = sol_refund
+                        .checked_add(refund)
+                        .ok_or(IpredictError::MathOverflow)?;
+                }
+                (OrderSide::Sell, TokenType::Yes) => {
+                    yes_tokens_returned = yes_tokens_returned
+                        .checked_add(remaining)
+                        .ok_or(IpredictError::MathOverflow)?;
+                }
+                (OrderSide::Sell, TokenType::No) => {
+                    no_tokens_returned = no_tokens_returned
+                        .checked_add(remaining)
+                        .ok_or(IpredictError::MathOverflow)?;
+                }
+            }
+            cancelled += 1;
+        }
+    }
+    drop(order_book);
+    
+    // Return the SOL still reserved by cancelled buy orders to the free balance
+    ctx.accounts.open_orders.credit_sol(sol_refund)?;
+    
+    // Return tokens from escrow for cancelled sell orders
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let wager_key = ctx.accounts.wager.key();
+    if yes_tokens_returned > 0 {
+        settlement::release_from_escrow(
+            &token_program,
+            &ctx.accounts.yes_escrow,
+            &ctx.accounts.yes_mint.to_account_info(),
+            &ctx.accounts.user_yes_account.to_account_info(),
+            &wager_key,
+            TokenType::Yes,
+            ctx.bumps.yes_escrow,
+            ctx.accounts.wager.token_decimals,
+            yes_tokens_returned,
+        )?;
+    }
+    if no_tokens_returned > 0 {
+        settlement::release_from_escrow(
+            &token_program,
+            &ctx.accounts.no_escrow,
+            &ctx.accounts.no_mint.to_account_info(),
+            &ctx.accounts.user_no_account.to_account_info(),
+            &wager_key,
+            TokenType::No,
+            ctx.bumps.no_escrow,
+            ctx.accounts.wager.token_decimals,
+            no_tokens_returned,
+        )?;
+    }
+    
+    msg!(
+        "Cancelled {} orders: released {} lamports, returned {} YES and {} NO tokens",
+        cancelled,
+        sol_refund,
+        yes_tokens_returned,
+        no_tokens_returned
+    );
+    
+    Ok(())
+}
\ No newline at end of file
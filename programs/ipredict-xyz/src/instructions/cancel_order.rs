@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{Wager, OrderBook, OrderSide, TokenType};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{Wager, OrderBook, OpenOrders, OrderSide, TokenType};
 use crate::constants::*;
 use crate::errors::IpredictError;
 
@@ -17,16 +17,22 @@ pub struct CancelOrder<'info> {
     
     #[account(
         mut,
-        seeds = [ORDER_BOOK_SEED, wager.key().as_ref()],
-        bump = order_book.bump
+        address = wager.order_book @ IpredictError::InvalidOrderBook
     )]
-    pub order_book: Box<Account<'info, OrderBook>>,
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
     
     #[account(
         mut,
         constraint = user_token_account.owner == user.key()
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     /// CHECK: Escrow account - validated by seeds
     #[account(
@@ -44,42 +50,42 @@ pub struct CancelOrder<'info> {
     )]
     pub no_escrow: AccountInfo<'info>,
     
+    #[account(
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn cancel_order(ctx: Context<CancelOrder>, order_id: u64) -> Result<()> {
-    let order_book = &mut ctx.accounts.order_book;
     let wager = &ctx.accounts.wager;
     
-    // Find and remove order from all order lists
-    let mut order = None;
-    let mut order_side = OrderSide::Buy;
-    let mut order_token_type = TokenType::Yes;
-    
-    // Search in all order lists
-    for (side, token_type, orders) in [
-        (OrderSide::Buy, TokenType::Yes, &mut order_book.buy_orders_yes),
-        (OrderSide::Sell, TokenType::Yes, &mut order_book.sell_orders_yes),
-        (OrderSide::Buy, TokenType::No, &mut order_book.buy_orders_no),
-        (OrderSide::Sell, TokenType::No, &mut order_book.sell_orders_no),
-    ] {
-        if let Some(pos) = orders.iter().position(|o| o.id == order_id) {
-            order = Some(orders.remove(pos));
-            order_side = side;
-            order_token_type = token_type;
-            break;
+    // Find and remove the order from whichever side it rests on
+    let order = {
+        let mut order_book = ctx.accounts.order_book.load_mut()?;
+        let order = order_book.find_order(order_id).ok_or(IpredictError::OrderNotFound)?;
+        
+        // Verify ownership
+        if order.owner != ctx.accounts.user.key() {
+            return Err(IpredictError::Unauthorized.into());
         }
-    }
-    
-    let order = order.ok_or(IpredictError::OrderNotFound)?;
-    
-    // Verify ownership
-    if order.owner != ctx.accounts.user.key() {
-        return Err(IpredictError::Unauthorized.into());
-    }
+        
+        order_book.remove_order(order_id, order.side(), order.token_type())?
+    };
+    ctx.accounts.open_orders.remove_order(order_id);
+    let order_side = order.side();
+    let order_token_type = order.token_type();
     
     // Return tokens from escrow for sell orders
     if order_side == OrderSide::Sell {
@@ -103,20 +109,25 @@ pub fn cancel_order(ctx: Context<CancelOrder>, order_id: u64) -> Result<()> {
             let escrow_seeds = &[
                 b"escrow",
                 wager_key.as_ref(),
-                token_seed.as_ref(),
+                token_seed,
                 &[escrow_bump],
             ];
             let escrow_signer = &[&escrow_seeds[..]];
             
             // Transfer tokens back to user
-            let cpi_accounts = Transfer {
+            let token_mint = match order_token_type {
+                TokenType::Yes => ctx.accounts.yes_mint.to_account_info(),
+                TokenType::No => ctx.accounts.no_mint.to_account_info(),
+            };
+            let cpi_accounts = TransferChecked {
                 from: escrow_account.to_account_info(),
+                mint: token_mint,
                 to: ctx.accounts.user_token_account.to_account_info(),
                 authority: escrow_account.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
             let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, escrow_signer);
-            token::transfer(cpi_ctx, remaining)?;
+            token_interface::transfer_checked(cpi_ctx, remaining, wager.token_decimals)?;
             
             msg!(
                 "Cancelled sell order {}, returned {} {} tokens from escrow",
@@ -126,7 +137,13 @@ pub fn cancel_order(ctx: Context<CancelOrder>, order_id: u64) -> Result<()> {
             );
         }
     } else {
-        msg!("Cancelled buy order {}", order_id);
+        // Return the SOL still reserved by the unfilled quantity to the free balance
+        let refund = order.remaining_quantity()
+            .checked_mul(order.price)
+            .ok_or(IpredictError::MathOverflow)?;
+        ctx.accounts.open_orders.credit_sol(refund)?;
+        
+        msg!("Cancelled buy order {}, released {} lamports to balance", order_id, refund);
     }
     
     Ok(())
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolverCommittee};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct CancelRotation<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        address = wager.committee @ IpredictError::InvalidCommittee
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+    
+    pub member: Signer<'info>,
+}
+
+/// Vote to drop the pending committee rotation numbered `nonce`. Once the
+/// current threshold of members agree it is discarded, and any member can
+/// open a new proposal.
+pub fn cancel_rotation(ctx: Context<CancelRotation>, nonce: u64) -> Result<()> {
+    let committee = &mut ctx.accounts.committee;
+    
+    if ctx.accounts.wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    let index = committee.member_index(&ctx.accounts.member.key())?;
+    let clock = Clock::get()?;
+    if !committee.rotation_pending(clock.unix_timestamp) || nonce != committee.rotation_nonce {
+        return Err(IpredictError::RotationProposalMismatch.into());
+    }
+    committee.rotation_cancellations |= 1 << index;
+    
+    let cancellations = committee.rotation_cancellations.count_ones() as u8;
+    msg!(
+        "Committee member {} voted to cancel rotation {} ({} of {} needed)",
+        ctx.accounts.member.key(),
+        nonce,
+        cancellations,
+        committee.threshold
+    );
+    
+    if cancellations >= committee.threshold {
+        committee.clear_rotation();
+        
+        msg!("Rotation {} for wager {} cancelled", nonce, ctx.accounts.wager.wager_id);
+    }
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{Wager, WagerStatus, Resolution, ResolutionArbitrator, DaoTally, VoteRecord};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct CastDaoVote<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = DaoTally::SIZE,
+        seeds = [DAO_TALLY_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub tally: Box<Account<'info, DaoTally>>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteRecord::SIZE,
+        seeds = [VOTE_RECORD_SEED, wager.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+    
+    #[account(address = wager.governance_mint @ IpredictError::DaoNotConfigured)]
+    pub governance_mint: Box<Account<'info, Mint>>,
+    
+    /// Holds every voter's locked governance tokens, owned by the wager PDA
+    #[account(
+        init_if_needed,
+        payer = voter,
+        token::mint = governance_mint,
+        token::authority = wager,
+        seeds = [DAO_VAULT_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub dao_vault: Box<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = governance_mint,
+        token::authority = voter
+    )]
+    pub voter_token_account: Box<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Lock governance tokens behind an outcome of a DAO-resolved wager during
+/// its voting period. A voter can add to their vote but not switch outcome.
+/// Votes are only counted once the period ends, by `finalize_dao_vote`.
+pub fn cast_dao_vote(
+    ctx: Context<CastDaoVote>,
+    resolution: Resolution,
+    amount: u64,
+) -> Result<()> {
+    let wager = &ctx.accounts.wager;
+    
+    if wager.resolution_arbitrator != ResolutionArbitrator::DAO {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    // Check the voting period is open
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    if clock.unix_timestamp >= wager.dao_voting_end {
+        return Err(IpredictError::VotingClosed.into());
+    }
+    
+    // Validate vote
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    if amount == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    
+    let vote_record = &mut ctx.accounts.vote_record;
+    if vote_record.amount > 0 && vote_record.resolution != resolution {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    
+    // Lock the tokens
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.voter_token_account.to_account_info(),
+        to: ctx.accounts.dao_vault.to_account_info(),
+        authority: ctx.accounts.voter.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+    
+    // Initialize vote record and tally if needed
+    if vote_record.voter == Pubkey::default() {
+        vote_record.wager = wager.key();
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.bump = ctx.bumps.vote_record;
+    }
+    vote_record.resolution = resolution;
+    vote_record.amount = vote_record.amount
+        .checked_add(amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    let tally = &mut ctx.accounts.tally;
+    if tally.wager == Pubkey::default() {
+        tally.wager = wager.key();
+        tally.bump = ctx.bumps.tally;
+    }
+    let votes = tally.add(resolution, amount)?;
+    
+    msg!(
+        "Voter {} locked {} behind {:?} on wager {} ({} in total)",
+        vote_record.voter,
+        amount,
+        resolution,
+        wager.wager_id,
+        votes
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Burn, Mint};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, Burn};
 use crate::state::{Wager, UserPosition, WagerStatus, Resolution};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::settlement::Collateral;
 
 #[derive(Accounts)]
 pub struct ClaimWinnings<'info> {
@@ -26,74 +28,89 @@ pub struct ClaimWinnings<'info> {
     #[account(
         mut,
         associated_token::mint = wager.yes_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_yes_account: Box<Account<'info, TokenAccount>>,
+    pub user_yes_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         associated_token::mint = wager.no_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_no_account: Box<Account<'info, TokenAccount>>,
+    pub user_no_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         seeds = [b"yes_mint", wager.key().as_ref()],
         bump
     )]
-    pub yes_mint: Box<Account<'info, Mint>>,
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         mut,
         seeds = [b"no_mint", wager.key().as_ref()],
         bump
     )]
-    pub no_mint: Box<Account<'info, Mint>>,
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         mut,
-        seeds = [VAULT_SEED, wager.key().as_ref()],
-        bump
+        address = wager.vault @ IpredictError::InvalidVault
     )]
-    /// CHECK: This is the vault PDA
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
     pub vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
     let wager = &ctx.accounts.wager;
     let user_position = &mut ctx.accounts.user_position;
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
     
     // Check wager is resolved
     if wager.status != WagerStatus::Resolved {
         return Err(IpredictError::WagerNotResolvable.into());
     }
     
-    // Check not already claimed
-    if user_position.winnings_claimed {
+    // Check not already claimed; invalid-market refunds burn what they pay
+    // for, so tokens settled later can still be refunded
+    if user_position.winnings_claimed && wager.resolution != Resolution::Invalid {
         return Err(IpredictError::Unauthorized.into());
     }
     
-    let mut winning_tokens = 0u64;
+    let winning_tokens: u64;
     let winning_mint: AccountInfo;
-    let winning_account: &TokenAccount;
+    let winning_account: AccountInfo;
     
     match wager.resolution {
         Resolution::YesWon => {
             winning_tokens = ctx.accounts.user_yes_account.amount;
             winning_mint = ctx.accounts.yes_mint.to_account_info();
-            winning_account = &**ctx.accounts.user_yes_account;
+            winning_account = ctx.accounts.user_yes_account.to_account_info();
         }
         Resolution::NoWon => {
             winning_tokens = ctx.accounts.user_no_account.amount;
             winning_mint = ctx.accounts.no_mint.to_account_info();
-            winning_account = &**ctx.accounts.user_no_account;
+            winning_account = ctx.accounts.user_no_account.to_account_info();
         }
         Resolution::Draw => {
             // In case of draw, users can claim based on total tokens
@@ -104,61 +121,75 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
             if total_tokens > 0 {
                 // Calculate payout (half value for draw)
                 let payout = total_tokens
-                    .checked_mul(LAMPORTS_PER_TOKEN)
+                    .checked_mul(wager.collateral_per_set)
                     .ok_or(IpredictError::MathOverflow)?
                     .checked_div(2)
                     .ok_or(IpredictError::MathOverflow)?;
                 
-                // Burn YES tokens if any
-                if yes_tokens > 0 {
-                    let cpi_accounts = Burn {
-                        mint: ctx.accounts.yes_mint.to_account_info(),
-                        from: ctx.accounts.user_yes_account.to_account_info(),
-                        authority: ctx.accounts.user.to_account_info(),
-                    };
-                    let cpi_program = ctx.accounts.token_program.to_account_info();
-                    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-                    token::burn(cpi_ctx, yes_tokens)?;
-                }
+                burn_holdings(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &ctx.accounts.user.to_account_info(),
+                    [
+                        (ctx.accounts.yes_mint.to_account_info(), ctx.accounts.user_yes_account.to_account_info(), yes_tokens),
+                        (ctx.accounts.no_mint.to_account_info(), ctx.accounts.user_no_account.to_account_info(), no_tokens),
+                    ],
+                )?;
                 
-                // Burn NO tokens if any
-                if no_tokens > 0 {
-                    let cpi_accounts = Burn {
-                        mint: ctx.accounts.no_mint.to_account_info(),
-                        from: ctx.accounts.user_no_account.to_account_info(),
-                        authority: ctx.accounts.user.to_account_info(),
-                    };
-                    let cpi_program = ctx.accounts.token_program.to_account_info();
-                    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-                    token::burn(cpi_ctx, no_tokens)?;
-                }
+                // Transfer collateral from vault
+                collateral.pay_from_vault(&ctx.accounts.vault, &ctx.accounts.user_collateral, payout)?;
                 
-                // Transfer SOL from vault
-                let wager_key = wager.key();
-                let vault_seeds = &[
-                    VAULT_SEED,
-                    wager_key.as_ref(),
-                    &[ctx.bumps.vault],
-                ];
-                let vault_signer = &[&vault_seeds[..]];
+                user_position.total_sol_withdrawn = user_position.total_sol_withdrawn
+                    .checked_add(payout)
+                    .ok_or(IpredictError::MathOverflow)?;
                 
-                let cpi_context = CpiContext::new_with_signer(
-                    ctx.accounts.system_program.to_account_info(),
-                    anchor_lang::system_program::Transfer {
-                        from: ctx.accounts.vault.to_account_info(),
-                        to: ctx.accounts.user.to_account_info(),
-                    },
-                    vault_signer,
+                msg!("Draw resolution: claimed {} SOL for {} total tokens", 
+                    payout as f64 / LAMPORTS_PER_SOL as f64,
+                    total_tokens
                 );
-                anchor_lang::system_program::transfer(cpi_context, payout)?;
+            }
+            
+            user_position.winnings_claimed = true;
+            return Ok(());
+        }
+        Resolution::Invalid => {
+            // Refund pro rata to the outstanding supply of both tokens. Each
+            // pair is backed by one set's collateral, so a complete set gets
+            // its full value back and a single token half, and the last
+            // holder to claim takes exactly what is left in the vault.
+            let yes_tokens = ctx.accounts.user_yes_account.amount;
+            let no_tokens = ctx.accounts.user_no_account.amount;
+            let total_tokens = yes_tokens.checked_add(no_tokens).ok_or(IpredictError::MathOverflow)?;
+            
+            if total_tokens > 0 {
+                let supply = ctx.accounts.yes_mint.supply
+                    .checked_add(ctx.accounts.no_mint.supply)
+                    .ok_or(IpredictError::MathOverflow)?;
+                let available = collateral.vault_balance(&ctx.accounts.vault)?;
+                let payout = (available as u128)
+                    .checked_mul(total_tokens as u128)
+                    .and_then(|value| value.checked_div(supply as u128))
+                    .ok_or(IpredictError::MathOverflow)? as u64;
+                
+                burn_holdings(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &ctx.accounts.user.to_account_info(),
+                    [
+                        (ctx.accounts.yes_mint.to_account_info(), ctx.accounts.user_yes_account.to_account_info(), yes_tokens),
+                        (ctx.accounts.no_mint.to_account_info(), ctx.accounts.user_no_account.to_account_info(), no_tokens),
+                    ],
+                )?;
+                
+                // Transfer collateral from vault
+                collateral.pay_from_vault(&ctx.accounts.vault, &ctx.accounts.user_collateral, payout)?;
                 
                 user_position.total_sol_withdrawn = user_position.total_sol_withdrawn
                     .checked_add(payout)
                     .ok_or(IpredictError::MathOverflow)?;
                 
-                msg!("Draw resolution: claimed {} SOL for {} total tokens", 
+                msg!("Invalid resolution: refunded {} SOL for {} YES and {} NO tokens",
                     payout as f64 / LAMPORTS_PER_SOL as f64,
-                    total_tokens
+                    yes_tokens,
+                    no_tokens
                 );
             }
             
@@ -171,37 +202,21 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
     if winning_tokens > 0 {
         // Calculate payout (full value for winners)
         let payout = winning_tokens
-            .checked_mul(LAMPORTS_PER_TOKEN)
+            .checked_mul(wager.collateral_per_set)
             .ok_or(IpredictError::MathOverflow)?;
         
         // Burn winning tokens
         let cpi_accounts = Burn {
             mint: winning_mint,
-            from: winning_account.to_account_info(),
+            from: winning_account,
             authority: ctx.accounts.user.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::burn(cpi_ctx, winning_tokens)?;
+        token_interface::burn(cpi_ctx, winning_tokens)?;
         
-        // Transfer SOL from vault
-        let wager_key = wager.key();
-        let vault_seeds = &[
-            VAULT_SEED,
-            wager_key.as_ref(),
-            &[ctx.bumps.vault],
-        ];
-        let vault_signer = &[&vault_seeds[..]];
-        
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            anchor_lang::system_program::Transfer {
-                from: ctx.accounts.vault.to_account_info(),
-                to: ctx.accounts.user.to_account_info(),
-            },
-            vault_signer,
-        );
-        anchor_lang::system_program::transfer(cpi_context, payout)?;
+        // Transfer collateral from vault
+        collateral.pay_from_vault(&ctx.accounts.vault, &ctx.accounts.user_collateral, payout)?;
         
         user_position.total_sol_withdrawn = user_position.total_sol_withdrawn
             .checked_add(payout)
@@ -221,5 +236,26 @@ pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
     
     user_position.winnings_claimed = true;
     
+    Ok(())
+}
+
+/// Burn a user's YES and NO tokens being paid out, as (mint, account, amount)
+fn burn_holdings<'info>(
+    token_program: &AccountInfo<'info>,
+    user: &AccountInfo<'info>,
+    holdings: [(AccountInfo<'info>, AccountInfo<'info>, u64); 2],
+) -> Result<()> {
+    for (mint, from, amount) in holdings {
+        if amount == 0 {
+            continue;
+        }
+        let cpi_accounts = Burn {
+            mint,
+            from,
+            authority: user.clone(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)?;
+    }
     Ok(())
 }
\ No newline at end of file
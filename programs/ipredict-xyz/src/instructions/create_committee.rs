@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, Resolution, ResolutionArbitrator, ResolverCommittee};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct CreateCommittee<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump,
+        constraint = wager.creator == creator.key() @ IpredictError::Unauthorized
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = ResolverCommittee::SIZE,
+        seeds = [COMMITTEE_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Hand a wager's resolution to an M-of-N committee. Only the creator can do
+/// this, and only before trading opens, so traders always know who resolves
+/// the wager they trade; votes are taken from `resolution_time` until
+/// `vote_deadline`.
+pub fn create_committee(
+    ctx: Context<CreateCommittee>,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    vote_deadline: i64,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    if wager.resolution_arbitrator != ResolutionArbitrator::Platform {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    let clock = Clock::get()?;
+    if !wager.accepts_committee(&clock) {
+        return Err(IpredictError::TradingAlreadyOpen.into());
+    }
+    if vote_deadline <= wager.resolution_time || vote_deadline >= wager.void_time() {
+        return Err(IpredictError::InvalidTimeParameters.into());
+    }
+    ResolverCommittee::validate(&members, threshold)?;
+    
+    let committee = &mut ctx.accounts.committee;
+    committee.wager = wager.key();
+    committee.votes = vec![Resolution::Pending; members.len()];
+    committee.members = members;
+    committee.threshold = threshold;
+    committee.vote_deadline = vote_deadline;
+    committee.pending_members = Vec::new();
+    committee.pending_threshold = 0;
+    committee.rotation_approvals = 0;
+    committee.rotation_cancellations = 0;
+    committee.rotation_nonce = 0;
+    committee.rotation_expires_at = 0;
+    committee.bump = ctx.bumps.committee;
+    
+    wager.committee = committee.key();
+    wager.resolution_arbitrator = ResolutionArbitrator::Committee;
+    
+    msg!(
+        "Wager {} resolved by a {}-of-{} committee, voting until {}",
+        wager.wager_id,
+        threshold,
+        committee.members.len(),
+        vote_deadline
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,7 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{
+    self, InitializeAccount3, InitializeMint2, MetadataPointerInitialize, TokenInterface,
+    TokenMetadataInitialize,
+};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use anchor_spl::token_2022::spl_token_2022::{self, extension::ExtensionType};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{Platform, Wager, OrderBook, WagerStatus, Resolution, ResolutionArbitrator};
+use crate::state::{Platform, Wager, OrderBook, BidEscrow, WagerStatus, Resolution, ResolutionArbitrator, OracleComparator, OracleCondition, TokenType};
 use crate::constants::*;
 use crate::errors::IpredictError;
 
@@ -27,43 +35,84 @@ pub struct CreateWager<'info> {
     )]
     pub wager: Account<'info, Wager>,
     
+    /// CHECK: Created in the handler under `token_program`
     #[account(
-        init,
-        payer = creator,
-        mint::decimals = 0,
-        mint::authority = wager,
+        mut,
         seeds = [b"yes_mint", wager.key().as_ref()],
         bump
     )]
-    pub yes_mint: Account<'info, Mint>,
+    pub yes_mint: AccountInfo<'info>,
+    
+    /// CHECK: Created in the handler under `token_program`
+    #[account(
+        mut,
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: AccountInfo<'info>,
+    
+    /// Pre-allocated by the client with `OrderBook::SIZE` bytes, owned by this program
+    #[account(zero)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    /// CHECK: Created in the handler under `token_program`
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: AccountInfo<'info>,
+    
+    /// CHECK: Created in the handler under `token_program`
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, wager.key().as_ref(), b"no"],
+        bump
+    )]
+    pub no_escrow: AccountInfo<'info>,
     
     #[account(
         init,
         payer = creator,
-        mint::decimals = 0,
-        mint::authority = wager,
-        seeds = [b"no_mint", wager.key().as_ref()],
+        space = BidEscrow::SIZE,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, wager.key().as_ref()],
         bump
     )]
-    pub no_mint: Account<'info, Mint>,
+    pub vault: SystemAccount<'info>,
     
+    /// SPL collateral mint; omit for a native SOL wager
+    pub collateral_mint: Option<Box<Account<'info, Mint>>>,
+    
+    /// Collateral vault for an SPL wager, owned by the wager PDA
     #[account(
         init,
         payer = creator,
-        space = OrderBook::SIZE,
-        seeds = [ORDER_BOOK_SEED, wager.key().as_ref()],
+        token::mint = collateral_mint,
+        token::authority = wager,
+        token::token_program = collateral_token_program,
+        seeds = [COLLATERAL_VAULT_SEED, wager.key().as_ref()],
         bump
     )]
-    pub order_book: Account<'info, OrderBook>,
+    pub collateral_vault: Option<Box<Account<'info, TokenAccount>>>,
     
+    /// Bid escrow collateral for an SPL wager, owned by the wager PDA
     #[account(
         init,
         payer = creator,
-        seeds = [VAULT_SEED, wager.key().as_ref()],
-        bump,
-        space = 8
+        token::mint = collateral_mint,
+        token::authority = wager,
+        token::token_program = collateral_token_program,
+        seeds = [BID_ESCROW_VAULT_SEED, wager.key().as_ref()],
+        bump
     )]
-    pub vault: SystemAccount<'info>,
+    pub bid_escrow_vault: Option<Box<Account<'info, TokenAccount>>>,
     
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -71,12 +120,15 @@ pub struct CreateWager<'info> {
     #[account(mut)]
     pub fee_recipient: SystemAccount<'info>,
     
-    pub token_program: Program<'info, Token>,
+    /// SPL Token for plain outcome mints, Token-2022 for mints with metadata
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_wager(
     ctx: Context<CreateWager>,
     name: String,
@@ -84,11 +136,13 @@ pub fn create_wager(
     opening_time: i64,
     closing_time: i64,
     resolution_time: i64,
+    metadata_uri: String,
+    collateral_per_set: u64,
+    token_decimals: u8,
+    resolution_arbitrator: ResolutionArbitrator,
+    oracle_condition: Option<OracleCondition>,
+    attester: Option<Pubkey>,
 ) -> Result<()> {
-    let platform = &mut ctx.accounts.platform;
-    let wager = &mut ctx.accounts.wager;
-    let order_book = &mut ctx.accounts.order_book;
-    
     // Validate inputs
     if name.len() > MAX_NAME_LENGTH {
         return Err(IpredictError::NameTooLong.into());
@@ -96,6 +150,9 @@ pub fn create_wager(
     if description.len() > MAX_DESCRIPTION_LENGTH {
         return Err(IpredictError::DescriptionTooLong.into());
     }
+    if metadata_uri.len() > MAX_URI_LENGTH {
+        return Err(IpredictError::UriTooLong.into());
+    }
     
     let clock = Clock::get()?;
     if opening_time < clock.unix_timestamp || 
@@ -104,6 +161,74 @@ pub fn create_wager(
         return Err(IpredictError::InvalidTimeParameters.into());
     }
     
+    // A set must split into two positive outcome prices
+    if collateral_per_set < 2 || token_decimals > MAX_TOKEN_DECIMALS {
+        return Err(IpredictError::InvalidContractSize.into());
+    }
+    
+    // Oracle wagers need a price condition and AI wagers an attester, and
+    // only they take one; committees are attached after creation with
+    // `create_committee`
+    match (resolution_arbitrator, &oracle_condition, &attester) {
+        (ResolutionArbitrator::Platform, None, None) => {}
+        (ResolutionArbitrator::AI, None, Some(_)) => {}
+        (ResolutionArbitrator::AI, None, None) => return Err(IpredictError::InvalidAttestation.into()),
+        (ResolutionArbitrator::DAO, None, None) => {
+            if ctx.accounts.platform.governance_mint == Pubkey::default() {
+                return Err(IpredictError::DaoNotConfigured.into());
+            }
+        }
+        (ResolutionArbitrator::Oracle, Some(condition), None) => {
+            // A between condition needs an ordered strike range
+            if condition.comparator == OracleComparator::Between && condition.upper_strike < condition.strike {
+                return Err(IpredictError::InvalidOracleCondition.into());
+            }
+        }
+        (ResolutionArbitrator::Oracle, None, None) => return Err(IpredictError::InvalidOracleCondition.into()),
+        _ => return Err(IpredictError::InvalidArbitrator.into()),
+    }
+    
+    // Create the outcome mints and their escrows under the chosen token program
+    let wager_id = ctx.accounts.platform.total_wagers_created;
+    let wager_key = ctx.accounts.wager.key();
+    let wager_id_bytes = wager_id.to_le_bytes();
+    let wager_seeds: &[&[u8]] = &[WAGER_SEED, wager_id_bytes.as_ref(), &[ctx.bumps.wager]];
+    let with_metadata = ctx.accounts.token_program.key() == spl_token_2022::ID;
+    
+    for (token_type, label) in [(TokenType::Yes, "YES"), (TokenType::No, "NO")] {
+        let (mint, mint_seed, mint_bump) = match token_type {
+            TokenType::Yes => (&ctx.accounts.yes_mint, b"yes_mint".as_ref(), ctx.bumps.yes_mint),
+            TokenType::No => (&ctx.accounts.no_mint, b"no_mint".as_ref(), ctx.bumps.no_mint),
+        };
+        let (escrow, escrow_bump) = match token_type {
+            TokenType::Yes => (&ctx.accounts.yes_escrow, ctx.bumps.yes_escrow),
+            TokenType::No => (&ctx.accounts.no_escrow, ctx.bumps.no_escrow),
+        };
+        let metadata = with_metadata.then(|| OutcomeMetadata {
+            name: format!("{} #{}: {}", label, wager_id, name),
+            symbol: label.to_string(),
+            uri: metadata_uri.clone(),
+        });
+        
+        create_outcome_mint(
+            ctx.accounts,
+            mint,
+            &[mint_seed, wager_key.as_ref(), &[mint_bump]],
+            wager_seeds,
+            token_decimals,
+            metadata,
+        )?;
+        create_outcome_escrow(
+            ctx.accounts,
+            escrow,
+            mint,
+            &[ESCROW_SEED, wager_key.as_ref(), token_type.to_seed(), &[escrow_bump]],
+        )?;
+    }
+    
+    let platform = &mut ctx.accounts.platform;
+    let wager = &mut ctx.accounts.wager;
+    
     // Transfer creation fee
     let cpi_context = CpiContext::new(
         ctx.accounts.system_program.to_account_info(),
@@ -114,20 +239,88 @@ pub fn create_wager(
     );
     anchor_lang::system_program::transfer(cpi_context, platform.wager_creation_fee)?;
     
+    // Escrow the reward for a correct resolution proposal in the wager itself
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: wager.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, platform.proposer_reward)?;
+    
+    // Pick the collateral: SPL wagers hold it in token accounts created above,
+    // native SOL wagers in the vault PDA and the bid escrow itself
+    match (
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.collateral_vault,
+        &ctx.accounts.bid_escrow_vault,
+    ) {
+        (Some(collateral_mint), Some(collateral_vault), Some(bid_escrow_vault)) => {
+            wager.collateral_mint = collateral_mint.key();
+            wager.vault = collateral_vault.key();
+            wager.bid_escrow_vault = bid_escrow_vault.key();
+        }
+        (None, None, None) => {
+            // Fund the collateral vault up to rent exemption so small deposits are accepted
+            let vault_rent = ctx.accounts.rent.minimum_balance(0);
+            let vault_shortfall = vault_rent.saturating_sub(ctx.accounts.vault.lamports());
+            if vault_shortfall > 0 {
+                let cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                );
+                anchor_lang::system_program::transfer(cpi_context, vault_shortfall)?;
+            }
+            
+            wager.collateral_mint = Pubkey::default();
+            wager.vault = ctx.accounts.vault.key();
+            wager.bid_escrow_vault = ctx.accounts.bid_escrow.key();
+        }
+        _ => return Err(IpredictError::InvalidCollateralAccount.into()),
+    }
+    
     // Initialize wager
     wager.creator = ctx.accounts.creator.key();
     wager.name = name;
     wager.description = description;
     wager.yes_mint = ctx.accounts.yes_mint.key();
     wager.no_mint = ctx.accounts.no_mint.key();
-    wager.vault = ctx.accounts.vault.key();
-    wager.order_book = order_book.key();
+    wager.order_book = ctx.accounts.order_book.key();
+    wager.bid_escrow = ctx.accounts.bid_escrow.key();
     wager.opening_time = opening_time;
     wager.closing_time = closing_time;
     wager.resolution_time = resolution_time;
+    wager.collateral_per_set = collateral_per_set;
+    wager.token_decimals = token_decimals;
     wager.status = WagerStatus::Created;
     wager.resolution = Resolution::Pending;
-    wager.resolution_arbitrator = ResolutionArbitrator::Platform;
+    wager.resolution_arbitrator = resolution_arbitrator;
+    wager.oracle_condition = oracle_condition;
+    wager.committee = Pubkey::default();
+    wager.attester = attester.unwrap_or_default();
+    
+    // A DAO vote runs on the terms in force when the wager was created
+    if resolution_arbitrator == ResolutionArbitrator::DAO {
+        wager.governance_mint = platform.governance_mint;
+        wager.dao_voting_end = resolution_time
+            .checked_add(platform.dao_voting_period)
+            .ok_or(IpredictError::MathOverflow)?;
+        wager.dao_quorum = platform.dao_quorum;
+    } else {
+        wager.governance_mint = Pubkey::default();
+        wager.dao_voting_end = 0;
+        wager.dao_quorum = 0;
+    }
+    
+    wager.halted_at = 0;
+    wager.last_fill_at = 0;
+    wager.event_time = 0;
+    wager.resolution_reward = platform.proposer_reward;
+    wager.resolution_grace_period = platform.resolution_grace_period;
     wager.total_yes_tokens = 0;
     wager.total_no_tokens = 0;
     wager.total_sol_deposited = 0;
@@ -135,15 +328,16 @@ pub fn create_wager(
     wager.total_fees_collected = 0;
     wager.wager_id = platform.total_wagers_created;
     wager.bump = ctx.bumps.wager;
+    wager.vault_bump = ctx.bumps.vault;
     
     // Initialize order book
-    order_book.wager = wager.key();
-    order_book.next_order_id = 0;
-    order_book.buy_orders_yes = Vec::new();
-    order_book.sell_orders_yes = Vec::new();
-    order_book.buy_orders_no = Vec::new();
-    order_book.sell_orders_no = Vec::new();
-    order_book.bump = ctx.bumps.order_book;
+    ctx.accounts.order_book.load_init()?.init(wager.key());
+    
+    // Initialize bid escrow
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
+    bid_escrow.wager = wager.key();
+    bid_escrow.total_locked = 0;
+    bid_escrow.bump = ctx.bumps.bid_escrow;
     
     // Update platform stats
     platform.total_wagers_created += 1;
@@ -154,4 +348,136 @@ pub fn create_wager(
     }
     
     Ok(())
+}
+
+/// Token metadata written onto Token-2022 outcome mints
+struct OutcomeMetadata {
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+/// Create an outcome mint PDA with the wager as mint authority. With
+/// metadata, the mint is a Token-2022 mint whose metadata pointer refers to
+/// itself and whose token metadata is updatable by the wager.
+fn create_outcome_mint<'info>(
+    accounts: &CreateWager<'info>,
+    mint: &AccountInfo<'info>,
+    mint_seeds: &[&[u8]],
+    wager_seeds: &[&[u8]],
+    decimals: u8,
+    metadata: Option<OutcomeMetadata>,
+) -> Result<()> {
+    let token_program = accounts.token_program.to_account_info();
+    let wager_key = accounts.wager.key();
+    
+    let space = if metadata.is_some() {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::MetadataPointer])?
+    } else {
+        spl_token_2022::state::Mint::LEN
+    };
+    // Token-2022 grows the mint to fit the metadata, so fund that up front
+    let metadata_space = match &metadata {
+        Some(metadata) => TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(wager_key))?,
+            mint: mint.key(),
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            additional_metadata: vec![],
+        }
+        .tlv_size_of()?,
+        None => 0,
+    };
+    let lamports = Rent::get()?.minimum_balance(space + metadata_space);
+    
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: accounts.creator.to_account_info(),
+                to: mint.clone(),
+            },
+            &[mint_seeds],
+        ),
+        lamports,
+        space as u64,
+        token_program.key,
+    )?;
+    
+    if metadata.is_some() {
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(wager_key),
+            Some(mint.key()),
+        )?;
+    }
+    
+    token_interface::initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: mint.clone() }),
+        decimals,
+        &wager_key,
+        None,
+    )?;
+    
+    if let Some(metadata) = metadata {
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    token_program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: accounts.wager.to_account_info(),
+                    mint_authority: accounts.wager.to_account_info(),
+                    mint: mint.clone(),
+                },
+                &[wager_seeds],
+            ),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )?;
+    }
+    
+    Ok(())
+}
+
+/// Create a self-owned escrow token account PDA for an outcome mint
+fn create_outcome_escrow<'info>(
+    accounts: &CreateWager<'info>,
+    escrow: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    escrow_seeds: &[&[u8]],
+) -> Result<()> {
+    let token_program = accounts.token_program.to_account_info();
+    let space = spl_token_2022::state::Account::LEN;
+    
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: accounts.creator.to_account_info(),
+                to: escrow.clone(),
+            },
+            &[escrow_seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+    
+    token_interface::initialize_account3(CpiContext::new(
+        token_program,
+        InitializeAccount3 {
+            account: escrow.clone(),
+            mint: mint.clone(),
+            authority: escrow.clone(),
+        },
+    ))
 }
\ No newline at end of file
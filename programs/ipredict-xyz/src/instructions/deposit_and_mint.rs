@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{Wager, UserPosition, WagerStatus};
+use crate::state::{Wager, UserPosition};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::settlement::Collateral;
 
 #[derive(Accounts)]
 pub struct DepositAndMint<'info> {
@@ -22,30 +24,32 @@ pub struct DepositAndMint<'info> {
         seeds = [b"yes_mint", wager.key().as_ref()],
         bump
     )]
-    pub yes_mint: Box<Account<'info, Mint>>,
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         mut,
         seeds = [b"no_mint", wager.key().as_ref()],
         bump
     )]
-    pub no_mint: Box<Account<'info, Mint>>,
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = yes_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_yes_account: Box<Account<'info, TokenAccount>>,
+    pub user_yes_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = no_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_no_account: Box<Account<'info, TokenAccount>>,
+    pub user_no_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         init_if_needed,
@@ -58,16 +62,23 @@ pub struct DepositAndMint<'info> {
     
     #[account(
         mut,
-        seeds = [VAULT_SEED, wager.key().as_ref()],
-        bump
+        address = wager.vault @ IpredictError::InvalidVault
     )]
-    /// CHECK: This is the vault PDA
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
     pub vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -82,23 +93,30 @@ pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()>
         return Err(IpredictError::WagerNotOpen.into());
     }
     
-    // Transfer SOL to vault
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: ctx.accounts.user.to_account_info(),
-            to: ctx.accounts.vault.to_account_info(),
-        },
-    );
-    anchor_lang::system_program::transfer(cpi_context, amount)?;
-    
-    // Calculate tokens to mint (100 tokens per SOL)
+    // Calculate complete sets to mint; collateral beyond whole sets stays with the user
     let tokens_to_mint = amount
-        .checked_mul(TOKENS_PER_SOL)
-        .ok_or(IpredictError::MathOverflow)?
-        .checked_div(LAMPORTS_PER_SOL)
+        .checked_div(wager.collateral_per_set)
+        .ok_or(IpredictError::MathOverflow)?;
+    if tokens_to_mint == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    let collateral_amount = tokens_to_mint
+        .checked_mul(wager.collateral_per_set)
         .ok_or(IpredictError::MathOverflow)?;
     
+    // Transfer collateral to vault
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    collateral.deposit(
+        &ctx.accounts.user_collateral,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.vault,
+        collateral_amount,
+    )?;
+    
     // Create signer seeds for the wager PDA
     let wager_id_bytes = wager.wager_id.to_le_bytes();
     let seeds = &[
@@ -116,7 +134,7 @@ pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()>
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::mint_to(cpi_ctx, tokens_to_mint)?;
+    token_interface::mint_to(cpi_ctx, tokens_to_mint)?;
     
     // Mint NO tokens
     let cpi_accounts = MintTo {
@@ -126,7 +144,7 @@ pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()>
     };
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::mint_to(cpi_ctx, tokens_to_mint)?;
+    token_interface::mint_to(cpi_ctx, tokens_to_mint)?;
     
     // Update wager stats
     wager.total_yes_tokens = wager.total_yes_tokens
@@ -136,7 +154,7 @@ pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()>
         .checked_add(tokens_to_mint)
         .ok_or(IpredictError::MathOverflow)?;
     wager.total_sol_deposited = wager.total_sol_deposited
-        .checked_add(amount)
+        .checked_add(collateral_amount)
         .ok_or(IpredictError::MathOverflow)?;
     
     // Initialize user position if needed
@@ -148,11 +166,11 @@ pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()>
     
     // Update user position stats
     user_position.total_sol_deposited = user_position.total_sol_deposited
-        .checked_add(amount)
+        .checked_add(collateral_amount)
         .ok_or(IpredictError::MathOverflow)?;
     
     msg!("Deposited {} SOL, minted {} YES and NO tokens", 
-        amount as f64 / LAMPORTS_PER_SOL as f64,
+        collateral_amount as f64 / LAMPORTS_PER_SOL as f64,
         tokens_to_mint
     );
     
@@ -1,19 +1,20 @@
 use anchor_lang::prelude::*;
-use crate::state::{Wager, UserPosition};
+use anchor_spl::token::Token;
+use crate::state::{Wager, UserPosition, OpenOrders, BidEscrow};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::settlement::Collateral;
 
 #[derive(Accounts)]
 pub struct DepositSol<'info> {
     #[account(
-        mut,
         seeds = [
             WAGER_SEED,
             wager.wager_id.to_le_bytes().as_ref()
         ],
         bump = wager.bump
     )]
-    pub wager: Account<'info, Wager>,
+    pub wager: Box<Account<'info, Wager>>,
     
     #[account(
         init_if_needed,
@@ -22,41 +23,66 @@ pub struct DepositSol<'info> {
         seeds = [USER_POSITION_SEED, user.key().as_ref(), wager.key().as_ref()],
         bump
     )]
-    pub user_position: Account<'info, UserPosition>,
+    pub user_position: Box<Account<'info, UserPosition>>,
     
     #[account(
         mut,
-        seeds = [VAULT_SEED, wager.key().as_ref()],
-        bump
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
     )]
-    /// CHECK: This is the vault PDA
-    pub vault: AccountInfo<'info>,
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+/// Credit collateral to the user's free balance for buy orders and `quick_buy`
 pub fn deposit_sol(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
-    let wager = &mut ctx.accounts.wager;
+    let wager = &ctx.accounts.wager;
     let user_position = &mut ctx.accounts.user_position;
     
     // Check wager is active
-    let clock = Clock::get()?;
-    if wager.status != crate::state::WagerStatus::Active {
+    if !wager.is_active(&Clock::get()?) {
         return Err(IpredictError::WagerNotOpen.into());
     }
     
-    // Transfer SOL to vault
-    let cpi_context = CpiContext::new(
+    // Hold the deposit in the bid escrow alongside locked bids
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.token_program.to_account_info(),
         ctx.accounts.system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: ctx.accounts.user.to_account_info(),
-            to: ctx.accounts.vault.to_account_info(),
-        },
     );
-    anchor_lang::system_program::transfer(cpi_context, amount)?;
+    collateral.lock_in_bid_escrow(
+        &ctx.accounts.user_collateral,
+        &ctx.accounts.user.to_account_info(),
+        &mut ctx.accounts.bid_escrow,
+        &ctx.accounts.bid_escrow_vault,
+        amount,
+    )?;
     
     // Initialize user position if needed
     if user_position.user == Pubkey::default() {
@@ -65,9 +91,12 @@ pub fn deposit_sol(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
         user_position.bump = ctx.bumps.user_position;
     }
     
+    ctx.accounts.open_orders.credit_sol(amount)?;
+    
     // Update stats
-    wager.total_sol_deposited += amount;
-    user_position.total_sol_deposited += amount;
+    user_position.total_sol_deposited = user_position.total_sol_deposited
+        .checked_add(amount)
+        .ok_or(IpredictError::MathOverflow)?;
     
     msg!("User {} deposited {} lamports", ctx.accounts.user.key(), amount);
     
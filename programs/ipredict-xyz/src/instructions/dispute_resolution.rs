@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolutionProposal};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct DisputeResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        seeds = [RESOLUTION_PROPOSAL_SEED, wager.key().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Challenge a proposed resolution by matching its bond, escalating the
+/// decision to the platform authority
+pub fn dispute_resolution(ctx: Context<DisputeResolution>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    // Only an undisputed proposal can be challenged
+    if wager.status != WagerStatus::Proposed {
+        return Err(IpredictError::NoResolutionProposed.into());
+    }
+    
+    let clock = Clock::get()?;
+    if clock.unix_timestamp >= ctx.accounts.proposal.dispute_deadline {
+        return Err(IpredictError::DisputeWindowClosed.into());
+    }
+    
+    // Match the proposer's bond
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.disputer.to_account_info(),
+            to: ctx.accounts.proposal.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, ctx.accounts.proposal.bond)?;
+    
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.disputer = ctx.accounts.disputer.key();
+    wager.status = WagerStatus::Disputed;
+    
+    msg!(
+        "Resolution {:?} for wager {} disputed by {}",
+        proposal.resolution,
+        wager.wager_id,
+        proposal.disputer
+    );
+    
+    Ok(())
+}
\ No newline at end of file
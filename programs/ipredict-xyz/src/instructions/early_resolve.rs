@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus, Resolution, ResolutionArbitrator, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::events::WagerResolvedEarly;
+
+#[derive(Accounts)]
+pub struct EarlyResolve<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    /// Evidence behind the resolution, published for disputes and indexers
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve a halted, platform-arbitrated wager before its resolution time.
+/// `event_time` records when the outcome actually became known, no later
+/// than the halt; trades made after it happened at a known outcome and can
+/// be flagged or reversed by comparing their timestamps against it.
+pub fn early_resolve(
+    ctx: Context<EarlyResolve>,
+    resolution: Resolution,
+    event_time: i64,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    // Check wager status
+    let clock = Clock::get()?;
+    if !wager.is_active(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    if wager.resolution_arbitrator != ResolutionArbitrator::Platform {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    if !wager.is_halted() {
+        return Err(IpredictError::TradingNotHalted.into());
+    }
+    
+    // Validate resolution
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    if event_time > wager.halted_at || event_time < wager.opening_time {
+        return Err(IpredictError::InvalidTimeParameters.into());
+    }
+    
+    // Update wager
+    wager.resolution = resolution;
+    wager.status = WagerStatus::Resolved;
+    wager.event_time = event_time;
+    
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.authority.key(),
+        resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    msg!(
+        "Wager {} resolved early as {:?}: event at {}, trading halted at {}",
+        wager.wager_id,
+        resolution,
+        event_time,
+        wager.halted_at
+    );
+    if wager.last_fill_at > event_time {
+        msg!("Wager {} last filled at {}, after its event", wager.wager_id, wager.last_fill_at);
+    }
+    emit!(WagerResolvedEarly {
+        wager: wager.key(),
+        resolution,
+        event_time,
+        halted_at: wager.halted_at,
+        last_fill_at: wager.last_fill_at,
+    });
+    
+    Ok(())
+}
\ No newline at end of file
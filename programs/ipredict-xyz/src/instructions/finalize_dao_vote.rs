@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolutionArbitrator, DaoTally, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct FinalizeDaoVote<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        seeds = [DAO_TALLY_SEED, wager.key().as_ref()],
+        bump = tally.bump
+    )]
+    pub tally: Account<'info, DaoTally>,
+    
+    /// Records the final tally behind the resolution
+    #[account(
+        init,
+        payer = caller,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    /// Anyone; receives the wager's resolution reward
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve a DAO wager once its voting period is over. If at least the
+/// wager's `dao_quorum` tokens were locked in total, the outcome with the most locked
+/// tokens wins; a tie for the lead leaves the wager to `resolve_wager`.
+pub fn finalize_dao_vote(ctx: Context<FinalizeDaoVote>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let tally = &ctx.accounts.tally;
+    
+    if wager.resolution_arbitrator != ResolutionArbitrator::DAO {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    // Check the voting period is over
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    if clock.unix_timestamp < wager.dao_voting_end {
+        return Err(IpredictError::VotingNotOver.into());
+    }
+    
+    let resolution = tally.decided(wager.dao_quorum).ok_or(IpredictError::DaoVoteUndecided)?;
+    
+    // Update wager
+    wager.resolution = resolution;
+    wager.status = WagerStatus::Resolved;
+    
+    let evidence = ResolutionEvidence {
+        source_uri: String::new(),
+        content_hash: [0; 32],
+        rationale: format!(
+            "DAO vote: {} yes, {} no, {} draw, {} invalid, quorum {}",
+            tally.yes_votes,
+            tally.no_votes,
+            tally.draw_votes,
+            tally.invalid_votes,
+            wager.dao_quorum
+        ),
+    };
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.caller.key(),
+        resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.caller, reward)?;
+    
+    msg!("Wager {} resolved as {:?} by DAO vote", wager.wager_id, resolution);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolutionProposal, ResolutionRecord};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct FinalizeResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [RESOLUTION_PROPOSAL_SEED, wager.key().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    
+    /// The proposer's evidence, published with the proposal
+    #[account(
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump = resolution_record.bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    /// CHECK: Receives the bond, the proposal's rent and the reward
+    #[account(
+        mut,
+        address = proposal.proposer @ IpredictError::Unauthorized
+    )]
+    pub proposer: AccountInfo<'info>,
+}
+
+/// Resolve a wager with its undisputed proposal once the dispute window has
+/// passed. Anyone can call this; the proposer gets their bond back plus the
+/// wager's resolution reward.
+pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let proposal = &ctx.accounts.proposal;
+    
+    if wager.status != WagerStatus::Proposed {
+        return Err(IpredictError::NoResolutionProposed.into());
+    }
+    
+    let clock = Clock::get()?;
+    if clock.unix_timestamp < proposal.dispute_deadline {
+        return Err(IpredictError::DisputeWindowOpen.into());
+    }
+    
+    // Update wager
+    wager.resolution = proposal.resolution;
+    wager.status = WagerStatus::Resolved;
+    
+    // Pay the reward; closing the proposal returns the bond
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.proposer, reward)?;
+    
+    msg!(
+        "Wager {} resolved as {:?}, proposer {} rewarded {} SOL",
+        wager.wager_id,
+        wager.resolution,
+        proposal.proposer,
+        reward as f64 / LAMPORTS_PER_SOL as f64
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus, Resolution, ResolutionProposal, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct ForceVoidUnresolved<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    /// Required if a resolution was proposed; its bonds are returned
+    #[account(
+        mut,
+        seeds = [RESOLUTION_PROPOSAL_SEED, wager.key().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Option<Account<'info, ResolutionProposal>>,
+    
+    /// CHECK: The proposal's proposer, checked in the handler
+    #[account(mut)]
+    pub proposer: Option<AccountInfo<'info>>,
+    
+    /// CHECK: The proposal's disputer, checked in the handler
+    #[account(mut)]
+    pub disputer: Option<AccountInfo<'info>>,
+    
+    /// Records the void, replacing any proposer's evidence
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    /// Receives the wager's resolution reward
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Void a wager nobody resolved within the grace period it was created with, so its collateral can be refunded as for an `Invalid`
+/// resolution. Anyone can call this. A pending proposal is dropped and every
+/// bond returned to whoever posted it.
+pub fn force_void_unresolved(ctx: Context<ForceVoidUnresolved>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    let clock = Clock::get()?;
+    let void_time = wager.void_time();
+    if clock.unix_timestamp < void_time {
+        return Err(IpredictError::GracePeriodNotOver.into());
+    }
+    
+    // Return the bonds of a proposal that never settled
+    if matches!(wager.status, WagerStatus::Proposed | WagerStatus::Disputed) {
+        let proposal = ctx.accounts.proposal.as_ref().ok_or(IpredictError::NoResolutionProposed)?;
+        let proposer = ctx.accounts.proposer.as_ref().ok_or(IpredictError::Unauthorized)?;
+        if proposer.key() != proposal.proposer {
+            return Err(IpredictError::Unauthorized.into());
+        }
+        
+        if proposal.is_disputed() {
+            let disputer = ctx.accounts.disputer.as_ref().ok_or(IpredictError::Unauthorized)?;
+            if disputer.key() != proposal.disputer {
+                return Err(IpredictError::Unauthorized.into());
+            }
+            settlement::transfer_lamports(&proposal.to_account_info(), disputer, proposal.bond)?;
+        }
+        
+        // Closing returns the proposer's bond and the proposal's rent
+        proposal.close(proposer.clone())?;
+    }
+    
+    // Update wager
+    wager.resolution = Resolution::Invalid;
+    wager.status = WagerStatus::Resolved;
+    
+    let evidence = ResolutionEvidence {
+        source_uri: String::new(),
+        content_hash: [0; 32],
+        rationale: format!("Unresolved at {}, past the resolution grace period", void_time),
+    };
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.caller.key(),
+        Resolution::Invalid,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.caller, reward)?;
+    
+    msg!("Wager {} left unresolved past {}, voided for refunds", wager.wager_id, void_time);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus, ResolutionArbitrator};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct HaltTrading<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Stop trading as soon as a wager's outcome becomes known. Orders can no
+/// longer be placed, matched or filled and sets can no longer be minted;
+/// resting orders can be cancelled or pruned. Only platform-arbitrated
+/// wagers, which `early_resolve` can settle, can be halted.
+pub fn halt_trading(ctx: Context<HaltTrading>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    if wager.resolution_arbitrator != ResolutionArbitrator::Platform {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    if wager.is_halted() {
+        return Err(IpredictError::TradingHalted.into());
+    }
+    
+    let clock = Clock::get()?;
+    wager.halted_at = clock.unix_timestamp;
+    
+    msg!("Trading on wager {} halted at {}", wager.wager_id, wager.halted_at);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, OpenOrders};
+use crate::constants::*;
+
+#[derive(Accounts)]
+pub struct InitOpenOrders<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = OpenOrders::SIZE,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_open_orders(ctx: Context<InitOpenOrders>) -> Result<()> {
+    let open_orders = &mut ctx.accounts.open_orders;
+    
+    open_orders.owner = ctx.accounts.user.key();
+    open_orders.wager = ctx.accounts.wager.key();
+    open_orders.order_ids = Vec::new();
+    open_orders.sol_free = 0;
+    open_orders.yes_tokens_free = 0;
+    open_orders.no_tokens_free = 0;
+    open_orders.bump = ctx.bumps.open_orders;
+    
+    msg!("Open orders account created for wager {}", open_orders.wager);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::Platform;
+use crate::state::{Platform, SelfTradeBehavior};
 use crate::constants::*;
 
 #[derive(Accounts)]
@@ -19,7 +19,10 @@ pub struct InitializePlatform<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_platform(ctx: Context<InitializePlatform>) -> Result<()> {
+pub fn initialize_platform(
+    ctx: Context<InitializePlatform>,
+    self_trade_behavior: SelfTradeBehavior,
+) -> Result<()> {
     let platform = &mut ctx.accounts.platform;
     
     platform.authority = ctx.accounts.authority.key();
@@ -30,6 +33,14 @@ pub fn initialize_platform(ctx: Context<InitializePlatform>) -> Result<()> {
     platform.platform_fee_bps = PLATFORM_FEE_BPS;
     platform.deployer_fee_bps = DEPLOYER_FEE_BPS;
     platform.wager_creation_fee = WAGER_CREATION_FEE;
+    platform.self_trade_behavior = self_trade_behavior;
+    platform.resolution_bond = DEFAULT_RESOLUTION_BOND;
+    platform.dispute_window = DEFAULT_DISPUTE_WINDOW;
+    platform.proposer_reward = DEFAULT_PROPOSER_REWARD;
+    platform.resolution_grace_period = DEFAULT_RESOLUTION_GRACE_PERIOD;
+    platform.governance_mint = Pubkey::default();
+    platform.dao_voting_period = DEFAULT_DAO_VOTING_PERIOD;
+    platform.dao_quorum = 0;
     platform.bump = ctx.bumps.platform;
     
     Ok(())
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{Platform, Wager, OrderBook, OrderSide, TokenType};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Platform, Wager, WagerStatus, OrderBook, BidEscrow, Cross, OrderSide, TokenType};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::settlement::{Collateral, OpenOrdersSet, Settlement};
 
 #[derive(Accounts)]
 pub struct MatchOrders<'info> {
@@ -25,345 +27,196 @@ pub struct MatchOrders<'info> {
     
     #[account(
         mut,
-        seeds = [ORDER_BOOK_SEED, wager.key().as_ref()],
-        bump = order_book.bump
+        address = wager.order_book @ IpredictError::InvalidOrderBook
     )]
-    pub order_book: Box<Account<'info, OrderBook>>,
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
     
     #[account(
         mut,
         seeds = [b"escrow", wager.key().as_ref(), b"yes"],
         bump
     )]
-    pub yes_escrow: Box<Account<'info, TokenAccount>>,
+    pub yes_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
         seeds = [b"escrow", wager.key().as_ref(), b"no"],
         bump
     )]
-    pub no_escrow: Box<Account<'info, TokenAccount>>,
+    pub no_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
+    pub vault: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&platform.fee_recipient) @ IpredictError::Unauthorized
+    )]
     /// CHECK: Platform fee recipient
     pub platform_fee_recipient: AccountInfo<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = wager.collateral_account(&wager.creator) @ IpredictError::Unauthorized
+    )]
     /// CHECK: Creator fee recipient
     pub creator_fee_recipient: AccountInfo<'info>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn match_orders(
-    ctx: Context<MatchOrders>,
+/// Permissionless crank. Remaining accounts: the `OpenOrders` of every order
+/// owner that gets filled or cancelled by self-trade prevention. Proceeds are
+/// credited there and paid out by `settle_funds`.
+pub fn match_orders<'info>(
+    ctx: Context<'_, '_, '_, 'info, MatchOrders<'info>>,
     max_iterations: u8,
 ) -> Result<()> {
-    let wager = &ctx.accounts.wager;
-    let order_book = &mut ctx.accounts.order_book;
+    // Resting orders stop crossing once the wager resolves or the resolver
+    // halts trading
+    if ctx.accounts.wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    if ctx.accounts.wager.is_halted() {
+        return Err(IpredictError::TradingHalted.into());
+    }
+    
+    let order_book = &mut *ctx.accounts.order_book.load_mut()?;
     let platform = &mut ctx.accounts.platform;
+    let open_orders = OpenOrdersSet::load(&ctx.accounts.wager.key(), ctx.remaining_accounts)?;
+    let set_value = ctx.accounts.wager.collateral_per_set;
+    
+    let collateral = Collateral::new(
+        &ctx.accounts.wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    let clock = Clock::get()?;
+    let mut settlement = Settlement {
+        wager: &mut ctx.accounts.wager,
+        yes_mint: ctx.accounts.yes_mint.to_account_info(),
+        no_mint: ctx.accounts.no_mint.to_account_info(),
+        yes_escrow: ctx.accounts.yes_escrow.to_account_info(),
+        yes_escrow_bump: ctx.bumps.yes_escrow,
+        no_escrow: ctx.accounts.no_escrow.to_account_info(),
+        no_escrow_bump: ctx.bumps.no_escrow,
+        bid_escrow: &mut ctx.accounts.bid_escrow,
+        bid_escrow_vault: ctx.accounts.bid_escrow_vault.to_account_info(),
+        vault: ctx.accounts.vault.to_account_info(),
+        platform_fee_recipient: ctx.accounts.platform_fee_recipient.to_account_info(),
+        creator_fee_recipient: ctx.accounts.creator_fee_recipient.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        collateral,
+        open_orders,
+        volume: 0,
+        fees: 0,
+        now: clock.unix_timestamp,
+    };
     
     let mut iterations = 0u8;
-    let mut total_volume = 0u64;
-    let mut total_fees = 0u64;
     
-    // Match YES orders
-    {
-        let mut buy_idx = 0;
-        let mut sell_idx = 0;
-        let mut completed_orders = Vec::new();
+    while iterations < max_iterations {
+        let cross = match order_book.next_cross(set_value, clock.unix_timestamp) {
+            Some(cross) => cross,
+            None => break,
+        };
         
-        while buy_idx < order_book.buy_orders_yes.len() && 
-              sell_idx < order_book.sell_orders_yes.len() && 
-              iterations < max_iterations {
-            
-            // Get order details without holding mutable references
-            let (buy_price, buy_quantity, buy_filled, buy_owner, buy_id) = {
-                let buy_order = &order_book.buy_orders_yes[buy_idx];
-                (buy_order.price, buy_order.quantity, buy_order.filled_quantity, buy_order.owner, buy_order.id)
-            };
-            
-            let (sell_price, sell_quantity, sell_filled, sell_owner, sell_id) = {
-                let sell_order = &order_book.sell_orders_yes[sell_idx];
-                (sell_order.price, sell_order.quantity, sell_order.filled_quantity, sell_order.owner, sell_order.id)
-            };
-            
-            // Check if orders can match (buy price >= sell price)
-            if buy_price >= sell_price {
-                // Calculate match quantity
-                let buy_remaining = buy_quantity.saturating_sub(buy_filled);
-                let sell_remaining = sell_quantity.saturating_sub(sell_filled);
-                let match_quantity = buy_remaining.min(sell_remaining);
+        // Each crossing pairs the best order on two sides of the book
+        let (first, second) = match cross {
+            Cross::Direct(token_type) => ((OrderSide::Buy, token_type), (OrderSide::Sell, token_type)),
+            Cross::Mint => ((OrderSide::Buy, TokenType::Yes), (OrderSide::Buy, TokenType::No)),
+            Cross::Burn => ((OrderSide::Sell, TokenType::Yes), (OrderSide::Sell, TokenType::No)),
+        };
+        let (first_index, first_order) = order_book.best_order(first.0, first.1, clock.unix_timestamp).ok_or(IpredictError::OrderNotFound)?;
+        let (second_index, second_order) = order_book.best_order(second.0, second.1, clock.unix_timestamp).ok_or(IpredictError::OrderNotFound)?;
+        
+        // A bid and an ask from the same owner never trade with each other
+        if let Cross::Direct(_) = cross {
+            if first_order.owner == second_order.owner {
+                let first_is_newer = first_order.id > second_order.id;
+                let (newer, older) = if first_is_newer {
+                    (&first_order, &second_order)
+                } else {
+                    (&second_order, &first_order)
+                };
+                let (newer_cancelled, older_cancelled) = settlement.prevent_self_trade(newer, older)?;
+                let (first_cancelled, second_cancelled) = if first_is_newer {
+                    (newer_cancelled, older_cancelled)
+                } else {
+                    (older_cancelled, newer_cancelled)
+                };
                 
-                if match_quantity > 0 {
-                    // Calculate trade amount and fees
-                    let execution_price = buy_price
-                        .checked_add(sell_price)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(2)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let trade_amount = match_quantity
-                        .checked_mul(execution_price)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let fee = trade_amount
-                        .checked_mul(TOTAL_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(BPS_DIVISOR)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let platform_fee = fee
-                        .checked_mul(PLATFORM_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(TOTAL_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let creator_fee = fee.saturating_sub(platform_fee);
-                    
-                    // Transfer fees
-                    **ctx.accounts.platform_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                        .platform_fee_recipient
-                        .lamports()
-                        .checked_add(platform_fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    **ctx.accounts.creator_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                        .creator_fee_recipient
-                        .lamports()
-                        .checked_add(creator_fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    // Update order filled quantities
-                    {
-                        let buy_order = &mut order_book.buy_orders_yes[buy_idx];
-                        buy_order.filled_quantity = buy_order.filled_quantity
-                            .checked_add(match_quantity)
-                            .ok_or(IpredictError::MathOverflow)?;
-                        
-                        if buy_order.filled_quantity >= buy_order.quantity {
-                            completed_orders.push(("buy_yes".to_string(), buy_idx));
-                        }
-                    }
-                    
-                    {
-                        let sell_order = &mut order_book.sell_orders_yes[sell_idx];
-                        sell_order.filled_quantity = sell_order.filled_quantity
-                            .checked_add(match_quantity)
-                            .ok_or(IpredictError::MathOverflow)?;
-                        
-                        if sell_order.filled_quantity >= sell_order.quantity {
-                            completed_orders.push(("sell_yes".to_string(), sell_idx));
-                        }
-                    }
-                    
-                    // Transfer tokens from escrow to buyer
-                    let wager_key = wager.key();
-                    let escrow_seeds = &[
-                        b"escrow",
-                        wager_key.as_ref(),
-                        b"yes",
-                        &[ctx.bumps.yes_escrow],
-                    ];
-                    let escrow_signer = &[&escrow_seeds[..]];
-                    
-                    // In production: transfer to buyer's account
-                    // For now, tokens stay in escrow and we track ownership separately
-                    
-                    total_volume = total_volume
-                        .checked_add(trade_amount)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    total_fees = total_fees
-                        .checked_add(fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    msg!(
-                        "Matched YES orders: {} tokens at {} price, fee: {}",
-                        match_quantity,
-                        execution_price,
-                        fee
-                    );
-                    
-                    iterations += 1;
+                if order_book.tree_mut(first.0, first.1).reduce(first_index, first_cancelled)? {
+                    settlement.open_orders.remove_order(&first_order)?;
                 }
-            }
-            
-            // Move to next order
-            if buy_idx < order_book.buy_orders_yes.len() && 
-               sell_idx < order_book.sell_orders_yes.len() {
-                let buy_order = &order_book.buy_orders_yes[buy_idx];
-                let sell_order = &order_book.sell_orders_yes[sell_idx];
-                
-                if buy_order.filled_quantity >= buy_order.quantity {
-                    buy_idx += 1;
-                } else if sell_order.filled_quantity >= sell_order.quantity {
-                    sell_idx += 1;
-                } else {
-                    break; // No more matches possible
+                if order_book.tree_mut(second.0, second.1).reduce(second_index, second_cancelled)? {
+                    settlement.open_orders.remove_order(&second_order)?;
                 }
+                
+                iterations += 1;
+                continue;
             }
         }
         
-        // Remove completed orders in reverse order
-        completed_orders.sort_by(|a, b| b.1.cmp(&a.1));
-        for (order_type, idx) in completed_orders {
-            match order_type.as_str() {
-                "buy_yes" => { order_book.buy_orders_yes.remove(idx); }
-                "sell_yes" => { order_book.sell_orders_yes.remove(idx); }
-                _ => {}
-            }
-        }
-    }
-    
-    // Match NO orders (similar logic)
-    {
-        let mut buy_idx = 0;
-        let mut sell_idx = 0;
-        let mut completed_orders = Vec::new();
+        let match_quantity = first_order.remaining_quantity().min(second_order.remaining_quantity());
         
-        while buy_idx < order_book.buy_orders_no.len() && 
-              sell_idx < order_book.sell_orders_no.len() && 
-              iterations < max_iterations {
-            
-            // Get order details without holding mutable references
-            let (buy_price, buy_quantity, buy_filled, buy_owner, buy_id) = {
-                let buy_order = &order_book.buy_orders_no[buy_idx];
-                (buy_order.price, buy_order.quantity, buy_order.filled_quantity, buy_order.owner, buy_order.id)
-            };
-            
-            let (sell_price, sell_quantity, sell_filled, sell_owner, sell_id) = {
-                let sell_order = &order_book.sell_orders_no[sell_idx];
-                (sell_order.price, sell_order.quantity, sell_order.filled_quantity, sell_order.owner, sell_order.id)
-            };
-            
-            // Check if orders can match (buy price >= sell price)
-            if buy_price >= sell_price {
-                // Calculate match quantity
-                let buy_remaining = buy_quantity.saturating_sub(buy_filled);
-                let sell_remaining = sell_quantity.saturating_sub(sell_filled);
-                let match_quantity = buy_remaining.min(sell_remaining);
-                
-                if match_quantity > 0 {
-                    // Calculate trade amount and fees
-                    let execution_price = buy_price
-                        .checked_add(sell_price)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(2)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let trade_amount = match_quantity
-                        .checked_mul(execution_price)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let fee = trade_amount
-                        .checked_mul(TOTAL_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(BPS_DIVISOR)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let platform_fee = fee
-                        .checked_mul(PLATFORM_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?
-                        .checked_div(TOTAL_FEE_BPS as u64)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    let creator_fee = fee.saturating_sub(platform_fee);
-                    
-                    // Transfer fees
-                    **ctx.accounts.platform_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                        .platform_fee_recipient
-                        .lamports()
-                        .checked_add(platform_fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    **ctx.accounts.creator_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                        .creator_fee_recipient
-                        .lamports()
-                        .checked_add(creator_fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    // Update order filled quantities
-                    {
-                        let buy_order = &mut order_book.buy_orders_no[buy_idx];
-                        buy_order.filled_quantity = buy_order.filled_quantity
-                            .checked_add(match_quantity)
-                            .ok_or(IpredictError::MathOverflow)?;
-                        
-                        if buy_order.filled_quantity >= buy_order.quantity {
-                            completed_orders.push(("buy_no".to_string(), buy_idx));
-                        }
-                    }
-                    
-                    {
-                        let sell_order = &mut order_book.sell_orders_no[sell_idx];
-                        sell_order.filled_quantity = sell_order.filled_quantity
-                            .checked_add(match_quantity)
-                            .ok_or(IpredictError::MathOverflow)?;
-                        
-                        if sell_order.filled_quantity >= sell_order.quantity {
-                            completed_orders.push(("sell_no".to_string(), sell_idx));
-                        }
-                    }
-                    
-                    // Transfer tokens from escrow to buyer
-                    let wager_key = wager.key();
-                    let escrow_seeds = &[
-                        b"escrow",
-                        wager_key.as_ref(),
-                        b"no",
-                        &[ctx.bumps.no_escrow],
-                    ];
-                    let escrow_signer = &[&escrow_seeds[..]];
-                    
-                    // In production: transfer to buyer's account
-                    // For now, tokens stay in escrow and we track ownership separately
-                    
-                    total_volume = total_volume
-                        .checked_add(trade_amount)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    total_fees = total_fees
-                        .checked_add(fee)
-                        .ok_or(IpredictError::MathOverflow)?;
-                    
-                    msg!(
-                        "Matched NO orders: {} tokens at {} price, fee: {}",
-                        match_quantity,
-                        execution_price,
-                        fee
-                    );
-                    
-                    iterations += 1;
-                }
-            }
-            
-            // Move to next order
-            if buy_idx < order_book.buy_orders_no.len() && 
-               sell_idx < order_book.sell_orders_no.len() {
-                let buy_order = &order_book.buy_orders_no[buy_idx];
-                let sell_order = &order_book.sell_orders_no[sell_idx];
-                
-                if buy_order.filled_quantity >= buy_order.quantity {
-                    buy_idx += 1;
-                } else if sell_order.filled_quantity >= sell_order.quantity {
-                    sell_idx += 1;
-                } else {
-                    break; // No more matches possible
-                }
-            }
+        match cross {
+            Cross::Direct(token_type) => settlement.fill(token_type, &first_order, &second_order, match_quantity)?,
+            Cross::Mint => settlement.fill_mint(&first_order, &second_order, match_quantity, set_value)?,
+            Cross::Burn => settlement.fill_burn(&first_order, &second_order, match_quantity, set_value)?,
         }
         
-        // Remove completed orders in reverse order
-        completed_orders.sort_by(|a, b| b.1.cmp(&a.1));
-        for (order_type, idx) in completed_orders {
-            match order_type.as_str() {
-                "buy_no" => { order_book.buy_orders_no.remove(idx); }
-                "sell_no" => { order_book.sell_orders_no.remove(idx); }
-                _ => {}
-            }
+        // Update order filled quantities, dropping completed orders
+        if order_book.tree_mut(first.0, first.1).fill(first_index, match_quantity)? {
+            settlement.open_orders.remove_order(&first_order)?;
         }
+        if order_book.tree_mut(second.0, second.1).fill(second_index, match_quantity)? {
+            settlement.open_orders.remove_order(&second_order)?;
+        }
+        
+        iterations += 1;
     }
     
+    settlement.open_orders.exit()?;
+    
+    let total_volume = settlement.volume;
+    let total_fees = settlement.fees;
+    let wager = settlement.wager;
+    
     // Update stats
     platform.total_volume_traded = platform.total_volume_traded
         .checked_add(total_volume)
@@ -378,6 +231,9 @@ pub fn match_orders(
     wager.total_fees_collected = wager.total_fees_collected
         .checked_add(total_fees)
         .ok_or(IpredictError::MathOverflow)?;
+    if total_volume > 0 {
+        wager.last_fill_at = clock.unix_timestamp;
+    }
     
     msg!(
         "Order matching complete: {} iterations, {} volume, {} fees",
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, Burn};
+use crate::state::{Wager, UserPosition, WagerStatus};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement::Collateral;
+
+#[derive(Accounts)]
+pub struct MergeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = yes_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_yes_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = no_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_no_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::SIZE,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), wager.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+    
+    #[account(
+        mut,
+        address = wager.vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
+    pub vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Burn `amount` complete YES+NO sets and return their collateral
+pub fn merge_tokens(ctx: Context<MergeTokens>, amount: u64) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let user_position = &mut ctx.accounts.user_position;
+    
+    // Sets can be merged at any time until the wager resolves
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    if amount == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    if ctx.accounts.user_yes_account.amount < amount || ctx.accounts.user_no_account.amount < amount {
+        return Err(IpredictError::InsufficientBalance.into());
+    }
+    
+    let sol_amount = amount
+        .checked_mul(wager.collateral_per_set)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    // Burn YES tokens
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.yes_mint.to_account_info(),
+        from: ctx.accounts.user_yes_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token_interface::burn(cpi_ctx, amount)?;
+    
+    // Burn NO tokens
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.no_mint.to_account_info(),
+        from: ctx.accounts.user_no_account.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token_interface::burn(cpi_ctx, amount)?;
+    
+    // Return the collateral from the vault
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    collateral.pay_from_vault(&ctx.accounts.vault, &ctx.accounts.user_collateral, sol_amount)?;
+    
+    // Update wager stats
+    wager.total_yes_tokens = wager.total_yes_tokens
+        .checked_sub(amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    wager.total_no_tokens = wager.total_no_tokens
+        .checked_sub(amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    // Initialize user position if needed
+    if user_position.user == Pubkey::default() {
+        user_position.user = ctx.accounts.user.key();
+        user_position.wager = wager.key();
+        user_position.bump = ctx.bumps.user_position;
+    }
+    
+    // Update user position stats
+    user_position.total_sol_withdrawn = user_position.total_sol_withdrawn
+        .checked_add(sol_amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    msg!("Merged {} YES and NO tokens, returned {} SOL",
+        amount,
+        sol_amount as f64 / LAMPORTS_PER_SOL as f64
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,355 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, MintTo};
+use crate::state::{Platform, Wager, WagerStatus, OrderBook, Order, UserPosition, OpenOrders, BidEscrow, OrderSide, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement::{Collateral, OpenOrdersSet, Settlement};
+
+#[derive(Accounts)]
+pub struct MintAndSell<'info> {
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump
+    )]
+    pub platform: Box<Account<'info, Platform>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        address = wager.order_book @ IpredictError::InvalidOrderBook
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::SIZE,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), wager.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    /// Receives the kept side of the minted sets
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key()
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"no"],
+        bump
+    )]
+    pub no_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
+    pub vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&platform.fee_recipient) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Platform fee recipient
+    pub platform_fee_recipient: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&wager.creator) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Creator fee recipient
+    pub creator_fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Mint complete sets, keep one side and sell the other as a limit order at
+/// `min_price_for_other`: whatever crosses the best resting orders fills now,
+/// under the usual trading fees, and the rest stays on the book. Sale
+/// proceeds are credited to the user's `OpenOrders` for `settle_funds`.
+///
+/// Remaining accounts: the `OpenOrders` of every resting order's owner the
+/// sell order trades against.
+pub fn mint_and_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, MintAndSell<'info>>,
+    token_type_to_keep: TokenType,
+    sol_amount: u64,
+    min_price_for_other: u64,
+) -> Result<()> {
+    let token_type_to_sell = token_type_to_keep.opposite();
+    
+    // Validate that the user token account mint matches the kept side
+    if ctx.accounts.user_token_account.mint != ctx.accounts.wager.outcome_mint(token_type_to_keep) {
+        return Err(IpredictError::InvalidTokenMint.into());
+    }
+    
+    // Validate inputs
+    let set_value = ctx.accounts.wager.collateral_per_set;
+    if min_price_for_other == 0 || min_price_for_other > set_value {
+        return Err(IpredictError::InvalidOrderPrice.into());
+    }
+    
+    // Check wager is open
+    if ctx.accounts.wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    let clock = Clock::get()?;
+    if !ctx.accounts.wager.is_open(&clock) {
+        return Err(IpredictError::WagerNotOpen.into());
+    }
+    
+    // Calculate complete sets to mint; collateral beyond whole sets stays with the user
+    let sets = sol_amount
+        .checked_div(set_value)
+        .ok_or(IpredictError::MathOverflow)?;
+    if sets == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    let collateral_amount = sets
+        .checked_mul(set_value)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    // Step 1: Deposit the collateral behind the sets
+    let collateral = Collateral::new(
+        &ctx.accounts.wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    collateral.deposit(
+        &ctx.accounts.user_collateral,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.vault,
+        collateral_amount,
+    )?;
+    
+    // Step 2: Mint the kept side to the user and the sold side straight into escrow
+    let wager_id_bytes = ctx.accounts.wager.wager_id.to_le_bytes();
+    let seeds = &[
+        WAGER_SEED,
+        wager_id_bytes.as_ref(),
+        &[ctx.accounts.wager.bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    for token_type in [TokenType::Yes, TokenType::No] {
+        let (mint, escrow) = match token_type {
+            TokenType::Yes => (ctx.accounts.yes_mint.to_account_info(), ctx.accounts.yes_escrow.to_account_info()),
+            TokenType::No => (ctx.accounts.no_mint.to_account_info(), ctx.accounts.no_escrow.to_account_info()),
+        };
+        let to = if token_type == token_type_to_keep {
+            ctx.accounts.user_token_account.to_account_info()
+        } else {
+            escrow
+        };
+        
+        let cpi_accounts = MintTo {
+            mint,
+            to,
+            authority: ctx.accounts.wager.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::mint_to(cpi_ctx, sets)?;
+    }
+    
+    {
+        let wager = &mut ctx.accounts.wager;
+        wager.total_yes_tokens = wager.total_yes_tokens
+            .checked_add(sets)
+            .ok_or(IpredictError::MathOverflow)?;
+        wager.total_no_tokens = wager.total_no_tokens
+            .checked_add(sets)
+            .ok_or(IpredictError::MathOverflow)?;
+        wager.total_sol_deposited = wager.total_sol_deposited
+            .checked_add(collateral_amount)
+            .ok_or(IpredictError::MathOverflow)?;
+    }
+    
+    // Step 3: Sell the other side against the book, already escrowed
+    let order_book = &mut *ctx.accounts.order_book.load_mut()?;
+    let platform = &mut ctx.accounts.platform;
+    let mut order = Order::new(
+        order_book.next_order_id,
+        ctx.accounts.user.key(),
+        OrderSide::Sell,
+        token_type_to_sell,
+        min_price_for_other,
+        sets,
+        clock.unix_timestamp,
+    );
+    order.self_trade_behavior = platform.self_trade_behavior as u8;
+    order_book.next_order_id = order_book.next_order_id
+        .checked_add(1)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    let (total_volume, total_fees, self_trade_cancelled) = {
+        let wager_key = ctx.accounts.wager.key();
+        let mut open_orders = OpenOrdersSet::load(&wager_key, ctx.remaining_accounts)?;
+        open_orders.insert(&wager_key, &ctx.accounts.open_orders.to_account_info())?;
+        
+        let mut settlement = Settlement {
+            wager: &mut ctx.accounts.wager,
+            yes_mint: ctx.accounts.yes_mint.to_account_info(),
+            no_mint: ctx.accounts.no_mint.to_account_info(),
+            yes_escrow: ctx.accounts.yes_escrow.to_account_info(),
+            yes_escrow_bump: ctx.bumps.yes_escrow,
+            no_escrow: ctx.accounts.no_escrow.to_account_info(),
+            no_escrow_bump: ctx.bumps.no_escrow,
+            bid_escrow: &mut ctx.accounts.bid_escrow,
+            bid_escrow_vault: ctx.accounts.bid_escrow_vault.to_account_info(),
+            vault: ctx.accounts.vault.to_account_info(),
+            platform_fee_recipient: ctx.accounts.platform_fee_recipient.to_account_info(),
+            creator_fee_recipient: ctx.accounts.creator_fee_recipient.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            collateral,
+            open_orders,
+            volume: 0,
+            fees: 0,
+            now: clock.unix_timestamp,
+        };
+        let self_trade_cancelled = settlement.take(order_book, &mut order, set_value, clock.unix_timestamp)?;
+        settlement.open_orders.exit()?;
+        (settlement.volume, settlement.fees, self_trade_cancelled)
+    };
+    
+    // Pick up what matching credited to the user's own open orders
+    ctx.accounts.open_orders.reload()?;
+    
+    // Step 4: Rest whatever did not fill as a limit order
+    let unfilled = order.remaining_quantity();
+    if unfilled > 0 {
+        order_book.add_order(order)?;
+        ctx.accounts.open_orders.add_order(order.id)?;
+    }
+    
+    // Update stats
+    let wager = &mut ctx.accounts.wager;
+    platform.total_volume_traded = platform.total_volume_traded
+        .checked_add(total_volume)
+        .ok_or(IpredictError::MathOverflow)?;
+    platform.total_fees_collected = platform.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    wager.total_volume_traded = wager.total_volume_traded
+        .checked_add(total_volume)
+        .ok_or(IpredictError::MathOverflow)?;
+    wager.total_fees_collected = wager.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    if total_volume > 0 {
+        wager.last_fill_at = clock.unix_timestamp;
+    }
+    
+    // Initialize user position if needed
+    let user_position = &mut ctx.accounts.user_position;
+    if user_position.user == Pubkey::default() {
+        user_position.user = ctx.accounts.user.key();
+        user_position.wager = wager.key();
+        user_position.bump = ctx.bumps.user_position;
+    }
+    
+    // Update user position; only the sold side's filled quantity counts as
+    // sold, since a resting remainder may never trade
+    let filled = order.filled_quantity;
+    user_position.total_sol_deposited = user_position.total_sol_deposited
+        .checked_add(collateral_amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    match token_type_to_sell {
+        TokenType::Yes => {
+            user_position.yes_tokens_sold = user_position.yes_tokens_sold
+                .checked_add(filled)
+                .ok_or(IpredictError::MathOverflow)?;
+        }
+        TokenType::No => {
+            user_position.no_tokens_sold = user_position.no_tokens_sold
+                .checked_add(filled)
+                .ok_or(IpredictError::MathOverflow)?;
+        }
+    }
+    
+    msg!(
+        "Minted {} sets for {} SOL, kept {} and sold {} {} at {} lamports or better as order {}: {} filled, {} resting, {} cancelled",
+        sets,
+        collateral_amount as f64 / LAMPORTS_PER_SOL as f64,
+        match token_type_to_keep { TokenType::Yes => "YES", TokenType::No => "NO" },
+        sets,
+        match token_type_to_sell { TokenType::Yes => "YES", TokenType::No => "NO" },
+        min_price_for_other,
+        order.id,
+        order.filled_quantity,
+        unfilled,
+        self_trade_cancelled
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,19 +1,77 @@
 pub mod initialize_platform;
+pub mod set_resolution_config;
+pub mod set_dao_config;
 pub mod create_wager;
+pub mod deposit_sol;
+pub mod withdraw_sol;
 pub mod deposit_and_mint;
+pub mod merge_tokens;
+pub mod init_open_orders;
 pub mod place_order;
 pub mod cancel_order;
+pub mod amend_order;
+pub mod cancel_all_orders;
 pub mod match_orders;
+pub mod settle_funds;
 pub mod quick_buy;
+pub mod quick_sell;
+pub mod mint_and_sell;
+pub mod prune_expired_orders;
+pub mod halt_trading;
+pub mod resume_trading;
+pub mod early_resolve;
 pub mod resolve_wager;
+pub mod resolve_from_oracle;
+pub mod resolve_with_attestation;
+pub mod force_void_unresolved;
+pub mod create_committee;
+pub mod vote_resolution;
+pub mod rotate_committee;
+pub mod cancel_rotation;
+pub mod cast_dao_vote;
+pub mod finalize_dao_vote;
+pub mod withdraw_dao_vote;
+pub mod propose_resolution;
+pub mod dispute_resolution;
+pub mod finalize_resolution;
+pub mod resolve_dispute;
 pub mod claim_winnings;
 
 pub use initialize_platform::*;
+pub use set_resolution_config::*;
+pub use set_dao_config::*;
 pub use create_wager::*;
+pub use deposit_sol::*;
+pub use withdraw_sol::*;
 pub use deposit_and_mint::*;
+pub use merge_tokens::*;
+pub use init_open_orders::*;
 pub use place_order::*;
 pub use cancel_order::*;
+pub use amend_order::*;
+pub use cancel_all_orders::*;
 pub use match_orders::*;
+pub use settle_funds::*;
 pub use quick_buy::*;
+pub use quick_sell::*;
+pub use mint_and_sell::*;
+pub use prune_expired_orders::*;
+pub use halt_trading::*;
+pub use resume_trading::*;
+pub use early_resolve::*;
 pub use resolve_wager::*;
+pub use resolve_from_oracle::*;
+pub use resolve_with_attestation::*;
+pub use force_void_unresolved::*;
+pub use create_committee::*;
+pub use vote_resolution::*;
+pub use rotate_committee::*;
+pub use cancel_rotation::*;
+pub use cast_dao_vote::*;
+pub use finalize_dao_vote::*;
+pub use withdraw_dao_vote::*;
+pub use propose_resolution::*;
+pub use dispute_resolution::*;
+pub use finalize_resolution::*;
+pub use resolve_dispute::*;
 pub use claim_winnings::*;
\ No newline at end of file
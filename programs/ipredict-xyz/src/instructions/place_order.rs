@@ -1,14 +1,23 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{Wager, OrderBook, Order, UserPosition, OrderSide, TokenType};
+use crate::state::{Platform, Wager, OrderBook, Order, UserPosition, OpenOrders, BidEscrow, OrderSide, OrderType, SelfTradeBehavior, TokenType};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::settlement::{self, Collateral, OpenOrdersSet, Settlement};
 
 #[derive(Accounts)]
-#[instruction(side: OrderSide, token_type: TokenType)]
 pub struct PlaceOrder<'info> {
     #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump
+    )]
+    pub platform: Box<Account<'info, Platform>>,
+    
+    #[account(
+        mut,
         seeds = [
             WAGER_SEED,
             wager.wager_id.to_le_bytes().as_ref()
@@ -19,10 +28,9 @@ pub struct PlaceOrder<'info> {
     
     #[account(
         mut,
-        seeds = [ORDER_BOOK_SEED, wager.key().as_ref()],
-        bump = order_book.bump
+        address = wager.order_book @ IpredictError::InvalidOrderBook
     )]
-    pub order_book: Box<Account<'info, OrderBook>>,
+    pub order_book: AccountLoader<'info, OrderBook>,
     
     #[account(
         mut,
@@ -31,106 +39,124 @@ pub struct PlaceOrder<'info> {
     )]
     pub user_position: Box<Account<'info, UserPosition>>,
     
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
     #[account(
         mut,
         constraint = user_token_account.owner == user.key()
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
-        seeds = [b"escrow", wager.key().as_ref(), token_type.to_seed().as_ref()],
+        seeds = [b"escrow", wager.key().as_ref(), b"no"],
         bump
     )]
-    pub escrow_account: Box<Account<'info, TokenAccount>>,
+    pub no_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Collateral vault, a lamport PDA or a token account for SPL collateral
+    pub vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(
+        mut,
+        address = wager.collateral_account(&platform.fee_recipient) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Platform fee recipient
+    pub platform_fee_recipient: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&wager.creator) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Creator fee recipient
+    pub creator_fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-
-pub fn place_order(
-    ctx: Context<PlaceOrder>,
+/// Remaining accounts: the `OpenOrders` of every resting order's owner the new
+/// order trades against. Fills on both sides are credited to `OpenOrders` and
+/// paid out by `settle_funds`. `self_trade_behavior` defaults to the
+/// platform's.
+#[allow(clippy::too_many_arguments)]
+pub fn place_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceOrder<'info>>,
     side: OrderSide,
     token_type: TokenType,
+    order_type: OrderType,
     price: u64,
     quantity: u64,
+    expires_at: Option<i64>,
+    self_trade_behavior: Option<SelfTradeBehavior>,
 ) -> Result<()> {
-    let wager = &ctx.accounts.wager;
-    let order_book = &mut ctx.accounts.order_book;
+    let order_book = &mut *ctx.accounts.order_book.load_mut()?;
+    let platform = &mut ctx.accounts.platform;
     let user_position = &mut ctx.accounts.user_position;
     
     // Validate token account mint matches the token type
     let expected_mint = match token_type {
-        TokenType::Yes => wager.yes_mint,
-        TokenType::No => wager.no_mint,
+        TokenType::Yes => ctx.accounts.wager.yes_mint,
+        TokenType::No => ctx.accounts.wager.no_mint,
     };
     
     if ctx.accounts.user_token_account.mint != expected_mint {
         return Err(IpredictError::InvalidTokenMint.into());
     }
     
-    // Initialize escrow account if needed
-    if ctx.accounts.escrow_account.data_is_empty() {
-        // Create escrow account with proper seeds
-        let escrow_seeds = &[
-            b"escrow",
-            wager.key().as_ref(),
-            token_type.to_seed(),
-            &[ctx.bumps.escrow_account],
-        ];
-        
-        anchor_lang::system_program::create_account(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::CreateAccount {
-                    from: ctx.accounts.user.to_account_info(),
-                    to: ctx.accounts.escrow_account.to_account_info(),
-                },
-                &[escrow_seeds],
-            ),
-            ctx.accounts.rent.minimum_balance(TokenAccount::LEN),
-            TokenAccount::LEN as u64,
-            &ctx.accounts.token_program.key(),
-        )?;
-        
-        // Initialize token account
-        // Need to get mint account info from the wager
-        let mint_pubkey = match token_type {
-            TokenType::Yes => wager.yes_mint,
-            TokenType::No => wager.no_mint,
-        };
-        
-        // Initialize escrow as a token account for the correct mint
-        let init_account_ix = spl_token::instruction::initialize_account(
-            &spl_token::id(),
-            &ctx.accounts.escrow_account.key(),
-            &mint_pubkey,
-            &ctx.accounts.escrow_account.key(),
-        )?;
-        
-        anchor_lang::solana_program::program::invoke_signed(
-            &init_account_ix,
-            &[
-                ctx.accounts.escrow_account.to_account_info(),
-                ctx.accounts.rent.to_account_info(),
-            ],
-            &[escrow_seeds],
-        )?;
-    }
-    
-    // Validate escrow account mint
-    if ctx.accounts.escrow_account.mint != expected_mint {
-        return Err(IpredictError::InvalidEscrowMint.into());
-    }
-    
     // Validate inputs
-    if price == 0 || price > LAMPORTS_PER_TOKEN {
+    let set_value = ctx.accounts.wager.collateral_per_set;
+    if price == 0 || price > set_value {
         return Err(IpredictError::InvalidOrderPrice.into());
     }
     if quantity == 0 {
@@ -139,10 +165,55 @@ pub fn place_order(
     
     // Check wager is open
     let clock = Clock::get()?;
-    if !wager.is_open(&clock) {
+    if !ctx.accounts.wager.is_open(&clock) {
         return Err(IpredictError::WagerNotOpen.into());
     }
     
+    // Good-til-time orders must expire in the future
+    let expires_at = expires_at.unwrap_or(0);
+    if expires_at != 0 && expires_at <= clock.unix_timestamp {
+        return Err(IpredictError::InvalidOrderExpiry.into());
+    }
+    
+    let mut order = Order::new(
+        order_book.next_order_id,
+        ctx.accounts.user.key(),
+        side,
+        token_type,
+        price,
+        quantity,
+        clock.unix_timestamp,
+    );
+    order.expires_at = expires_at;
+    order.self_trade_behavior = self_trade_behavior.unwrap_or(platform.self_trade_behavior) as u8;
+    order_book.next_order_id = order_book.next_order_id
+        .checked_add(1)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    // Check time-in-force constraints before moving any funds
+    match order_type {
+        OrderType::PostOnly => {
+            if order_book.best_match(&order, set_value, clock.unix_timestamp).is_some() {
+                return Err(IpredictError::PostOnlyWouldCross.into());
+            }
+        }
+        OrderType::FillOrKill => {
+            if order_book.fillable_quantity(&order, set_value, clock.unix_timestamp) < quantity {
+                return Err(IpredictError::FillOrKillNotFilled.into());
+            }
+        }
+        OrderType::Limit | OrderType::ImmediateOrCancel => {}
+    }
+    
+    let (escrow_account, escrow_bump) = match token_type {
+        TokenType::Yes => (ctx.accounts.yes_escrow.to_account_info(), ctx.bumps.yes_escrow),
+        TokenType::No => (ctx.accounts.no_escrow.to_account_info(), ctx.bumps.no_escrow),
+    };
+    let token_mint = match token_type {
+        TokenType::Yes => ctx.accounts.yes_mint.to_account_info(),
+        TokenType::No => ctx.accounts.no_mint.to_account_info(),
+    };
+    
     // For sell orders, transfer tokens to escrow
     if side == OrderSide::Sell {
         // Check user has sufficient balance
@@ -151,74 +222,165 @@ pub fn place_order(
         }
         
         // Transfer tokens to escrow
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_token_account.to_account_info(),
-            to: ctx.accounts.escrow_account.to_account_info(),
+            mint: token_mint.clone(),
+            to: escrow_account.clone(),
             authority: ctx.accounts.user.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, quantity)?;
+        token_interface::transfer_checked(cpi_ctx, quantity, ctx.accounts.wager.token_decimals)?;
     } else {
-        // For buy orders, check user has sufficient SOL
+        // For buy orders, reserve the full notional from the free balance
         let required_sol = quantity
             .checked_mul(price)
             .ok_or(IpredictError::MathOverflow)?;
+        ctx.accounts.open_orders.debit_sol(required_sol)?;
+    }
+    
+    // Match against the resting book, unless the order may only rest
+    let user_token_account = ctx.accounts.user_token_account.to_account_info();
+    let (total_volume, total_fees, self_trade_cancelled) = if order_type == OrderType::PostOnly {
+        (0, 0, 0)
+    } else {
+        // Write back the reservation before matching loads the account
+        ctx.accounts.open_orders.exit(&crate::ID)?;
         
-        let user_balance = ctx.accounts.user.lamports();
-        if user_balance < required_sol {
-            return Err(IpredictError::InsufficientBalance.into());
-        }
+        let wager_key = ctx.accounts.wager.key();
+        let mut open_orders = OpenOrdersSet::load(&wager_key, ctx.remaining_accounts)?;
+        open_orders.insert(&wager_key, &ctx.accounts.open_orders.to_account_info())?;
+        
+        let collateral = Collateral::new(
+            &ctx.accounts.wager,
+            ctx.accounts.collateral_token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        );
+        let mut settlement = Settlement {
+            wager: &mut ctx.accounts.wager,
+            yes_mint: ctx.accounts.yes_mint.to_account_info(),
+            no_mint: ctx.accounts.no_mint.to_account_info(),
+            yes_escrow: ctx.accounts.yes_escrow.to_account_info(),
+            yes_escrow_bump: ctx.bumps.yes_escrow,
+            no_escrow: ctx.accounts.no_escrow.to_account_info(),
+            no_escrow_bump: ctx.bumps.no_escrow,
+            bid_escrow: &mut ctx.accounts.bid_escrow,
+            bid_escrow_vault: ctx.accounts.bid_escrow_vault.to_account_info(),
+            vault: ctx.accounts.vault.to_account_info(),
+            platform_fee_recipient: ctx.accounts.platform_fee_recipient.to_account_info(),
+            creator_fee_recipient: ctx.accounts.creator_fee_recipient.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            collateral,
+            open_orders,
+            volume: 0,
+            fees: 0,
+            now: clock.unix_timestamp,
+        };
+        let self_trade_cancelled = settlement.take(order_book, &mut order, set_value, clock.unix_timestamp)?;
+        settlement.open_orders.exit()?;
+        (settlement.volume, settlement.fees, self_trade_cancelled)
+    };
+    
+    // Pick up what matching credited to the user's own open orders
+    ctx.accounts.open_orders.reload()?;
+    
+    // Self-trade prevention may have cancelled part of a fill-or-kill order
+    if order_type == OrderType::FillOrKill && order.filled_quantity < quantity {
+        return Err(IpredictError::FillOrKillNotFilled.into());
     }
     
-    // Create order
-    let order = Order {
-        id: order_book.next_order_id,
-        owner: ctx.accounts.user.key(),
-        side,
-        token_type,
-        price,
-        quantity,
-        filled_quantity: 0,
-        timestamp: clock.unix_timestamp,
+    // Rest the remainder, or hand it back for immediate-only orders
+    let unfilled = order.remaining_quantity();
+    let unfilled_cancelled = match order_type {
+        _ if unfilled == 0 => 0,
+        OrderType::Limit | OrderType::PostOnly => {
+            order_book.add_order(order)?;
+            ctx.accounts.open_orders.add_order(order.id)?;
+            0
+        }
+        OrderType::ImmediateOrCancel | OrderType::FillOrKill => {
+            match side {
+                OrderSide::Buy => {
+                    let refund = unfilled
+                        .checked_mul(price)
+                        .ok_or(IpredictError::MathOverflow)?;
+                    ctx.accounts.open_orders.credit_sol(refund)?;
+                }
+                OrderSide::Sell => {
+                    settlement::release_from_escrow(
+                        &ctx.accounts.token_program.to_account_info(),
+                        &escrow_account,
+                        &token_mint,
+                        &user_token_account,
+                        &ctx.accounts.wager.key(),
+                        token_type,
+                        escrow_bump,
+                        ctx.accounts.wager.token_decimals,
+                        unfilled,
+                    )?;
+                }
+            }
+            unfilled
+        }
     };
     
-    // Add to order book
-    order_book.add_order(order)?;
-    order_book.next_order_id = order_book.next_order_id
-        .checked_add(1)
+    // Update stats
+    let wager = &mut ctx.accounts.wager;
+    platform.total_volume_traded = platform.total_volume_traded
+        .checked_add(total_volume)
         .ok_or(IpredictError::MathOverflow)?;
+    platform.total_fees_collected = platform.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    wager.total_volume_traded = wager.total_volume_traded
+        .checked_add(total_volume)
+        .ok_or(IpredictError::MathOverflow)?;
+    wager.total_fees_collected = wager.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    if total_volume > 0 {
+        wager.last_fill_at = clock.unix_timestamp;
+    }
     
-    // Update user position tracking
+    // Update user position tracking with what filled on placement; a resting
+    // remainder may never trade, so it is not counted
+    let cancelled = self_trade_cancelled + unfilled_cancelled;
+    let filled = order.filled_quantity;
     match (side, token_type) {
         (OrderSide::Buy, TokenType::Yes) => {
             user_position.yes_tokens_bought = user_position.yes_tokens_bought
-                .checked_add(quantity)
+                .checked_add(filled)
                 .ok_or(IpredictError::MathOverflow)?;
         }
         (OrderSide::Buy, TokenType::No) => {
             user_position.no_tokens_bought = user_position.no_tokens_bought
-                .checked_add(quantity)
+                .checked_add(filled)
                 .ok_or(IpredictError::MathOverflow)?;
         }
         (OrderSide::Sell, TokenType::Yes) => {
             user_position.yes_tokens_sold = user_position.yes_tokens_sold
-                .checked_add(quantity)
+                .checked_add(filled)
                 .ok_or(IpredictError::MathOverflow)?;
         }
         (OrderSide::Sell, TokenType::No) => {
             user_position.no_tokens_sold = user_position.no_tokens_sold
-                .checked_add(quantity)
+                .checked_add(filled)
                 .ok_or(IpredictError::MathOverflow)?;
         }
     }
     
     msg!(
-        "Order placed: {} {} {} tokens at {} lamports each",
+        "Order {} placed: {} {} {} tokens at {} lamports each ({:?}), {} filled, {} resting, {} cancelled",
+        order.id,
         match side { OrderSide::Buy => "BUY", OrderSide::Sell => "SELL" },
         quantity,
         match token_type { TokenType::Yes => "YES", TokenType::No => "NO" },
-        price
+        price,
+        order_type,
+        order.filled_quantity,
+        unfilled - unfilled_cancelled,
+        cancelled
     );
     
     Ok(())
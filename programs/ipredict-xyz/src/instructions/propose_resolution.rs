@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus, Resolution, ResolutionArbitrator, ResolutionProposal, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct ProposeResolution<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = ResolutionProposal::SIZE,
+        seeds = [RESOLUTION_PROPOSAL_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    
+    /// The proposer's evidence, replaced if the platform overturns the proposal
+    #[account(
+        init_if_needed,
+        payer = proposer,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose the outcome of a wager past its resolution time, staking the
+/// platform's resolution bond. The proposal becomes final unless disputed
+/// within the platform's dispute window.
+pub fn propose_resolution(
+    ctx: Context<ProposeResolution>,
+    resolution: Resolution,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
+    let platform = &ctx.accounts.platform;
+    let wager = &mut ctx.accounts.wager;
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    // Oracles and committees settle their own wagers
+    if wager.resolution_arbitrator != ResolutionArbitrator::Platform {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    // Check if resolution time has passed
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    
+    // Validate resolution
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    
+    // Stake the bond in the proposal account
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.proposer.to_account_info(),
+            to: ctx.accounts.proposal.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, platform.resolution_bond)?;
+    
+    let dispute_deadline = clock.unix_timestamp
+        .checked_add(platform.dispute_window)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.wager = wager.key();
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.resolution = resolution;
+    proposal.bond = platform.resolution_bond;
+    proposal.proposed_at = clock.unix_timestamp;
+    proposal.dispute_deadline = dispute_deadline;
+    proposal.disputer = Pubkey::default();
+    proposal.bump = ctx.bumps.proposal;
+    
+    wager.status = WagerStatus::Proposed;
+    
+    ctx.accounts.resolution_record.publish(
+        wager,
+        proposal.proposer,
+        resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    msg!(
+        "Resolution {:?} proposed for wager {} with a {} SOL bond, disputable until {}",
+        resolution,
+        wager.wager_id,
+        proposal.bond as f64 / LAMPORTS_PER_SOL as f64,
+        dispute_deadline
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, OrderBook, OrderSide, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement::OpenOrdersSet;
+
+#[derive(Accounts)]
+pub struct PruneExpiredOrders<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        address = wager.order_book @ IpredictError::InvalidOrderBook
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+}
+
+/// Permissionless crank. An order is pruned once its `expires_at` has passed,
+/// or once the wager is resolved or past its closing time; a halt alone can
+/// be lifted, so it leaves orders resting. What it still locked is credited
+/// to its owner's `OpenOrders` for `settle_funds`.
+///
+/// Remaining accounts: the `OpenOrders` of every pruned order's owner.
+pub fn prune_expired_orders<'info>(
+    ctx: Context<'_, '_, '_, 'info, PruneExpiredOrders<'info>>,
+    max_orders: u8,
+) -> Result<()> {
+    let wager = &ctx.accounts.wager;
+    let order_book = &mut *ctx.accounts.order_book.load_mut()?;
+    let mut open_orders = OpenOrdersSet::load(&wager.key(), ctx.remaining_accounts)?;
+    
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let trading_closed = wager.status == WagerStatus::Resolved || now >= wager.closing_time;
+    
+    let mut pruned = 0u8;
+    
+    for (side, token_type) in [
+        (OrderSide::Buy, TokenType::Yes),
+        (OrderSide::Sell, TokenType::Yes),
+        (OrderSide::Buy, TokenType::No),
+        (OrderSide::Sell, TokenType::No),
+    ] {
+        let tree = order_book.tree_mut(side, token_type);
+        
+        while pruned < max_orders {
+            let index = match tree.orders().iter().position(|order| trading_closed || order.is_expired(now)) {
+                Some(index) => index as u32,
+                None => break,
+            };
+            let order = tree.remove(index);
+            let remaining = order.remaining_quantity();
+            
+            // SOL stays in the bid escrow and tokens in the escrow until settled
+            match side {
+                OrderSide::Buy => {
+                    let refund = remaining
+                        .checked_mul(order.price)
+                        .ok_or(IpredictError::MathOverflow)?;
+                    open_orders.credit_sol(&order.owner, refund)?;
+                }
+                OrderSide::Sell => {
+                    open_orders.credit_tokens(&order.owner, token_type, remaining)?;
+                }
+            }
+            open_orders.remove_order(&order)?;
+            
+            msg!("Pruned expired order {}, returned {} unfilled", order.id, remaining);
+            pruned += 1;
+        }
+    }
+    
+    open_orders.exit()?;
+    
+    msg!("Pruned {} expired orders", pruned);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{Platform, Wager, OrderBook, UserPosition, OrderSide, TokenType};
+use crate::state::{Platform, Wager, WagerStatus, OrderBook, UserPosition, BidEscrow, OrderSide, SelfTradeBehavior, TokenType};
 use crate::constants::*;
 use crate::errors::IpredictError;
+use crate::events::OrderFilled;
+use crate::settlement::{Collateral, Fees, OpenOrdersSet};
 
 #[derive(Accounts)]
 #[instruction(token_type: TokenType)]
@@ -27,10 +30,9 @@ pub struct QuickBuy<'info> {
     
     #[account(
         mut,
-        seeds = [ORDER_BOOK_SEED, wager.key().as_ref()],
-        bump = order_book.bump
+        address = wager.order_book @ IpredictError::InvalidOrderBook
     )]
-    pub order_book: Box<Account<'info, OrderBook>>,
+    pub order_book: AccountLoader<'info, OrderBook>,
     
     #[account(
         init_if_needed,
@@ -45,42 +47,69 @@ pub struct QuickBuy<'info> {
         mut,
         constraint = user_token_account.owner == user.key()
     )]
-    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     
     #[account(
         mut,
-        seeds = [b"escrow", wager.key().as_ref(), token_type.to_seed().as_ref()],
+        seeds = [b"escrow", wager.key().as_ref(), token_type.to_seed()],
         bump
     )]
-    pub escrow_account: Box<Account<'info, TokenAccount>>,
+    pub escrow_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(address = wager.outcome_mint(token_type) @ IpredictError::InvalidTokenMint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = wager.collateral_account(&platform.fee_recipient) @ IpredictError::Unauthorized
+    )]
     /// CHECK: Platform fee recipient
     pub platform_fee_recipient: AccountInfo<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = wager.collateral_account(&wager.creator) @ IpredictError::Unauthorized
+    )]
     /// CHECK: Creator fee recipient
     pub creator_fee_recipient: AccountInfo<'info>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 
-pub fn quick_buy(
-    ctx: Context<QuickBuy>,
+/// Remaining accounts: the user's own `OpenOrders`, whose free SOL pays for
+/// the buy, and the `OpenOrders` of every seller whose order gets filled.
+/// Sellers' proceeds are credited there for `settle_funds`.
+pub fn quick_buy<'info>(
+    ctx: Context<'_, '_, '_, 'info, QuickBuy<'info>>,
     token_type: TokenType,
     sol_amount: u64,
     min_tokens_out: u64,
 ) -> Result<()> {
-    let wager = &ctx.accounts.wager;
-    let order_book = &mut ctx.accounts.order_book;
+    let wager = &mut ctx.accounts.wager;
     let platform = &mut ctx.accounts.platform;
     let user_position = &mut ctx.accounts.user_position;
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
     
     // Validate that the user token account mint matches the token type
     let expected_mint = match token_type {
@@ -93,35 +122,42 @@ pub fn quick_buy(
     }
     
     // Check wager is open
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
     let clock = Clock::get()?;
     if !wager.is_open(&clock) {
         return Err(IpredictError::WagerNotOpen.into());
     }
     
-    // Get sell orders for the requested token type
-    let sell_orders = match token_type {
-        TokenType::Yes => &mut order_book.sell_orders_yes,
-        TokenType::No => &mut order_book.sell_orders_no,
-    };
+    let bid_escrow_vault = ctx.accounts.bid_escrow_vault.to_account_info();
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    let platform_fee_recipient = ctx.accounts.platform_fee_recipient.to_account_info();
+    let creator_fee_recipient = ctx.accounts.creator_fee_recipient.to_account_info();
     
-    // Sort by price ascending (best price first)
-    sell_orders.sort_by(|a, b| a.price.cmp(&b.price));
+    let mut open_orders = OpenOrdersSet::load(&wager.key(), ctx.remaining_accounts)?;
+    let mut order_book = ctx.accounts.order_book.load_mut()?;
+    let sell_orders = order_book.tree_mut(OrderSide::Sell, token_type);
     
     let mut remaining_sol = sol_amount;
+    let mut sol_spent = 0u64;
     let mut tokens_bought = 0u64;
+    let mut tokens_returned = 0u64;
     let mut total_fees = 0u64;
-    let mut filled_orders = Vec::new();
     
-    // Execute market buy against sell orders
-    for (i, order) in sell_orders.iter_mut().enumerate() {
-        if remaining_sol == 0 {
-            break;
-        }
+    // Execute market buy against sell orders, best unexpired price first
+    while remaining_sol > 0 {
+        let index = match sell_orders.best_live(clock.unix_timestamp) {
+            Some(index) => index,
+            None => break,
+        };
+        let order = *sell_orders.order(index);
         
-        let available_tokens = order.quantity.saturating_sub(order.filled_quantity);
-        if available_tokens == 0 {
-            continue;
-        }
+        let available_tokens = order.remaining_quantity();
         
         // Calculate how many tokens we can buy with remaining SOL
         let max_tokens_affordable = remaining_sol
@@ -129,102 +165,116 @@ pub fn quick_buy(
             .unwrap_or(0);
         
         let tokens_to_buy = available_tokens.min(max_tokens_affordable);
+        if tokens_to_buy == 0 {
+            break;
+        }
         
-        if tokens_to_buy > 0 {
-            let cost = tokens_to_buy
-                .checked_mul(order.price)
-                .ok_or(IpredictError::MathOverflow)?;
+        // Never buy from the user's own order; the quick buy counts as the newer order
+        if order.owner == ctx.accounts.user.key() {
+            let (order_cancelled, stop) = match platform.self_trade_behavior {
+                SelfTradeBehavior::CancelNewest => (0, true),
+                SelfTradeBehavior::CancelOldest => (available_tokens, false),
+                SelfTradeBehavior::CancelBoth => (available_tokens, true),
+                SelfTradeBehavior::DecrementAndCancel => (tokens_to_buy, false),
+            };
             
-            // Calculate fees
-            let fee = cost
-                .checked_mul(TOTAL_FEE_BPS as u64)
-                .ok_or(IpredictError::MathOverflow)?
-                .checked_div(BPS_DIVISOR)
-                .ok_or(IpredictError::MathOverflow)?;
-            
-            let platform_fee = fee
-                .checked_mul(PLATFORM_FEE_BPS as u64)
-                .ok_or(IpredictError::MathOverflow)?
-                .checked_div(TOTAL_FEE_BPS as u64)
-                .ok_or(IpredictError::MathOverflow)?;
-            
-            let creator_fee = fee.saturating_sub(platform_fee);
-            let seller_receives = cost.saturating_sub(fee);
-            
-            // Transfer SOL to seller (simplified - in production would track seller accounts)
-            **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.user
-                .to_account_info()
-                .lamports()
-                .checked_sub(cost)
-                .ok_or(IpredictError::InsufficientBalance)?;
-            
-            // Transfer fees
-            **ctx.accounts.platform_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                .platform_fee_recipient
-                .lamports()
-                .checked_add(platform_fee)
-                .ok_or(IpredictError::MathOverflow)?;
-            
-            **ctx.accounts.creator_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts
-                .creator_fee_recipient
-                .lamports()
-                .checked_add(creator_fee)
-                .ok_or(IpredictError::MathOverflow)?;
-            
-            // Update order
-            order.filled_quantity = order.filled_quantity
-                .checked_add(tokens_to_buy)
-                .ok_or(IpredictError::MathOverflow)?;
-            
-            if order.filled_quantity >= order.quantity {
-                filled_orders.push(i);
+            // Cancelled tokens go back to the user along with their purchase
+            if sell_orders.reduce(index, order_cancelled)? {
+                open_orders.remove_order(&order)?;
             }
-            
-            // Update counters
-            tokens_bought = tokens_bought
-                .checked_add(tokens_to_buy)
-                .ok_or(IpredictError::MathOverflow)?;
-            remaining_sol = remaining_sol
-                .checked_sub(cost)
-                .ok_or(IpredictError::MathOverflow)?;
-            total_fees = total_fees
-                .checked_add(fee)
+            tokens_returned = tokens_returned
+                .checked_add(order_cancelled)
                 .ok_or(IpredictError::MathOverflow)?;
+            if platform.self_trade_behavior == SelfTradeBehavior::DecrementAndCancel {
+                // The overlap also comes off the user's budget, unspent
+                remaining_sol = remaining_sol
+                    .checked_sub(tokens_to_buy * order.price)
+                    .ok_or(IpredictError::MathOverflow)?;
+            }
             
-            msg!(
-                "Quick buy: bought {} tokens at {} price from order {}",
-                tokens_to_buy,
-                order.price,
-                order.id
-            );
+            msg!("Quick buy: self-trade prevention cancelled {} of order {}", order_cancelled, order.id);
+            if stop {
+                break;
+            }
+            continue;
+        }
+        
+        let cost = tokens_to_buy
+            .checked_mul(order.price)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        // Spend the buyer's free balance: fees leave the bid escrow and the
+        // seller's proceeds wait there until they settle
+        open_orders.debit_sol(&ctx.accounts.user.key(), cost)?;
+        let fees = Fees::on(cost)?;
+        open_orders.credit_sol(&order.owner, cost.saturating_sub(fees.total))?;
+        collateral.pay_from_bid_escrow(bid_escrow, &bid_escrow_vault, &platform_fee_recipient, fees.platform)?;
+        collateral.pay_from_bid_escrow(bid_escrow, &bid_escrow_vault, &creator_fee_recipient, fees.creator)?;
+        
+        // Update order, dropping it once filled
+        if sell_orders.fill(index, tokens_to_buy)? {
+            open_orders.remove_order(&order)?;
         }
+        
+        // Update counters
+        tokens_bought = tokens_bought
+            .checked_add(tokens_to_buy)
+            .ok_or(IpredictError::MathOverflow)?;
+        remaining_sol = remaining_sol
+            .checked_sub(cost)
+            .ok_or(IpredictError::MathOverflow)?;
+        sol_spent = sol_spent
+            .checked_add(cost)
+            .ok_or(IpredictError::MathOverflow)?;
+        total_fees = total_fees
+            .checked_add(fees.total)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        emit!(OrderFilled {
+            wager: wager.key(),
+            order_id: order.id,
+            owner: order.owner,
+            side: OrderSide::Sell,
+            token_type,
+            counterparty: ctx.accounts.user.key(),
+            quantity: tokens_to_buy,
+            price: order.price,
+            timestamp: clock.unix_timestamp,
+        });
+        msg!(
+            "Quick buy: bought {} tokens at {} price from order {}",
+            tokens_to_buy,
+            order.price,
+            order.id
+        );
     }
+    drop(order_book);
+    open_orders.exit()?;
     
     // Transfer tokens from escrow to buyer
-    if tokens_bought > 0 {
+    let tokens_out = tokens_bought
+        .checked_add(tokens_returned)
+        .ok_or(IpredictError::MathOverflow)?;
+    if tokens_out > 0 {
         let token_seed = token_type.to_seed();
         let wager_key = wager.key();
         let escrow_seeds = &[
             b"escrow",
             wager_key.as_ref(),
-            token_seed.as_ref(),
+            token_seed,
             &[ctx.bumps.escrow_account],
         ];
         let escrow_signer = &[&escrow_seeds[..]];
         
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.escrow_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
             to: ctx.accounts.user_token_account.to_account_info(),
             authority: ctx.accounts.escrow_account.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, escrow_signer);
-        token::transfer(cpi_ctx, tokens_bought)?;
-    }
-    
-    // Remove filled orders (in reverse order to maintain indices)
-    for i in filled_orders.iter().rev() {
-        sell_orders.remove(*i);
+        token_interface::transfer_checked(cpi_ctx, tokens_out, wager.token_decimals)?;
     }
     
     // Check slippage
@@ -234,18 +284,21 @@ pub fn quick_buy(
     
     // Update stats
     platform.total_volume_traded = platform.total_volume_traded
-        .checked_add(sol_amount.saturating_sub(remaining_sol))
+        .checked_add(sol_spent)
         .ok_or(IpredictError::MathOverflow)?;
     platform.total_fees_collected = platform.total_fees_collected
         .checked_add(total_fees)
         .ok_or(IpredictError::MathOverflow)?;
     
     wager.total_volume_traded = wager.total_volume_traded
-        .checked_add(sol_amount.saturating_sub(remaining_sol))
+        .checked_add(sol_spent)
         .ok_or(IpredictError::MathOverflow)?;
     wager.total_fees_collected = wager.total_fees_collected
         .checked_add(total_fees)
         .ok_or(IpredictError::MathOverflow)?;
+    if sol_spent > 0 {
+        wager.last_fill_at = clock.unix_timestamp;
+    }
     
     // Initialize user position if needed
     if user_position.user == Pubkey::default() {
@@ -269,12 +322,12 @@ pub fn quick_buy(
     }
     
     msg!(
-        "Quick buy complete: bought {} {} tokens for {} SOL (spent {}, {} refunded)",
+        "Quick buy complete: bought {} {} tokens for {} SOL (spent {}, {} left in balance)",
         tokens_bought,
         match token_type { TokenType::Yes => "YES", TokenType::No => "NO" },
         sol_amount as f64 / LAMPORTS_PER_SOL as f64,
-        (sol_amount - remaining_sol) as f64 / LAMPORTS_PER_SOL as f64,
-        remaining_sol as f64 / LAMPORTS_PER_SOL as f64
+        sol_spent as f64 / LAMPORTS_PER_SOL as f64,
+        (sol_amount - sol_spent) as f64 / LAMPORTS_PER_SOL as f64
     );
     
     Ok(())
@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{Platform, Wager, WagerStatus, OrderBook, UserPosition, BidEscrow, OrderSide, SelfTradeBehavior, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::events::OrderFilled;
+use crate::settlement::{Collateral, Fees, OpenOrdersSet};
+
+#[derive(Accounts)]
+#[instruction(token_type: TokenType)]
+pub struct QuickSell<'info> {
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump
+    )]
+    pub platform: Box<Account<'info, Platform>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        address = wager.order_book @ IpredictError::InvalidOrderBook
+    )]
+    pub order_book: AccountLoader<'info, OrderBook>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserPosition::SIZE,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), wager.key().as_ref()],
+        bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key()
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), token_type.to_seed()],
+        bump
+    )]
+    pub escrow_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(address = wager.outcome_mint(token_type) @ IpredictError::InvalidTokenMint)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&platform.fee_recipient) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Platform fee recipient
+    pub platform_fee_recipient: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&wager.creator) @ IpredictError::Unauthorized
+    )]
+    /// CHECK: Creator fee recipient
+    pub creator_fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Remaining accounts: the `OpenOrders` of every bidder whose order gets
+/// filled, including the user's own if self-trade prevention cancels one of
+/// their orders. Bidders' tokens are credited there for `settle_funds`.
+pub fn quick_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, QuickSell<'info>>,
+    token_type: TokenType,
+    token_amount: u64,
+    min_sol_out: u64,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let platform = &mut ctx.accounts.platform;
+    let user_position = &mut ctx.accounts.user_position;
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
+    
+    // Validate that the user token account mint matches the token type
+    let expected_mint = match token_type {
+        TokenType::Yes => wager.yes_mint,
+        TokenType::No => wager.no_mint,
+    };
+    
+    if ctx.accounts.user_token_account.mint != expected_mint {
+        return Err(IpredictError::InvalidTokenMint.into());
+    }
+    
+    if token_amount == 0 {
+        return Err(IpredictError::InvalidOrderQuantity.into());
+    }
+    if ctx.accounts.user_token_account.amount < token_amount {
+        return Err(IpredictError::InsufficientBalance.into());
+    }
+    
+    // Check wager is open
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    let clock = Clock::get()?;
+    if !wager.is_open(&clock) {
+        return Err(IpredictError::WagerNotOpen.into());
+    }
+    
+    let user = ctx.accounts.user.to_account_info();
+    let user_collateral = ctx.accounts.user_collateral.to_account_info();
+    let bid_escrow_vault = ctx.accounts.bid_escrow_vault.to_account_info();
+    let collateral = Collateral::new(
+        wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    let platform_fee_recipient = ctx.accounts.platform_fee_recipient.to_account_info();
+    let creator_fee_recipient = ctx.accounts.creator_fee_recipient.to_account_info();
+    
+    let mut open_orders = OpenOrdersSet::load(&wager.key(), ctx.remaining_accounts)?;
+    let mut order_book = ctx.accounts.order_book.load_mut()?;
+    let buy_orders = order_book.tree_mut(OrderSide::Buy, token_type);
+    
+    let mut remaining_tokens = token_amount;
+    let mut tokens_sold = 0u64;
+    let mut gross_proceeds = 0u64;
+    let mut sol_out = 0u64;
+    let mut total_fees = 0u64;
+    
+    // Execute market sell against buy orders, best unexpired price first
+    while remaining_tokens > 0 {
+        let index = match buy_orders.best_live(clock.unix_timestamp) {
+            Some(index) => index,
+            None => break,
+        };
+        let order = *buy_orders.order(index);
+        
+        let tokens_to_sell = order.remaining_quantity().min(remaining_tokens);
+        
+        // Never sell into the user's own order; the quick sell counts as the newer order
+        if order.owner == user.key() {
+            let (order_cancelled, stop) = match platform.self_trade_behavior {
+                SelfTradeBehavior::CancelNewest => (0, true),
+                SelfTradeBehavior::CancelOldest => (order.remaining_quantity(), false),
+                SelfTradeBehavior::CancelBoth => (order.remaining_quantity(), true),
+                SelfTradeBehavior::DecrementAndCancel => (tokens_to_sell, false),
+            };
+            
+            // Return the SOL the cancelled quantity reserved to the free balance
+            let refund = order_cancelled
+                .checked_mul(order.price)
+                .ok_or(IpredictError::MathOverflow)?;
+            open_orders.credit_sol(&order.owner, refund)?;
+            if buy_orders.reduce(index, order_cancelled)? {
+                open_orders.remove_order(&order)?;
+            }
+            if platform.self_trade_behavior == SelfTradeBehavior::DecrementAndCancel {
+                // The overlap also comes off the amount being sold
+                remaining_tokens = remaining_tokens
+                    .checked_sub(tokens_to_sell)
+                    .ok_or(IpredictError::MathOverflow)?;
+            }
+            
+            msg!("Quick sell: self-trade prevention cancelled {} of order {}", order_cancelled, order.id);
+            if stop {
+                break;
+            }
+            continue;
+        }
+        
+        let proceeds = tokens_to_sell
+            .checked_mul(order.price)
+            .ok_or(IpredictError::MathOverflow)?;
+        let fees = Fees::on(proceeds)?;
+        
+        // The bidder's tokens wait in escrow until they settle
+        open_orders.credit_tokens(&order.owner, token_type, tokens_to_sell)?;
+        
+        // Pay the seller and fees out of the bidder's locked collateral
+        collateral.pay_from_bid_escrow(bid_escrow, &bid_escrow_vault, &user_collateral, proceeds.saturating_sub(fees.total))?;
+        collateral.pay_from_bid_escrow(bid_escrow, &bid_escrow_vault, &platform_fee_recipient, fees.platform)?;
+        collateral.pay_from_bid_escrow(bid_escrow, &bid_escrow_vault, &creator_fee_recipient, fees.creator)?;
+        
+        // Update order, dropping it once filled
+        if buy_orders.fill(index, tokens_to_sell)? {
+            open_orders.remove_order(&order)?;
+        }
+        
+        // Update counters
+        remaining_tokens = remaining_tokens
+            .checked_sub(tokens_to_sell)
+            .ok_or(IpredictError::MathOverflow)?;
+        tokens_sold = tokens_sold
+            .checked_add(tokens_to_sell)
+            .ok_or(IpredictError::MathOverflow)?;
+        gross_proceeds = gross_proceeds
+            .checked_add(proceeds)
+            .ok_or(IpredictError::MathOverflow)?;
+        sol_out = sol_out
+            .checked_add(proceeds.saturating_sub(fees.total))
+            .ok_or(IpredictError::MathOverflow)?;
+        total_fees = total_fees
+            .checked_add(fees.total)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        emit!(OrderFilled {
+            wager: wager.key(),
+            order_id: order.id,
+            owner: order.owner,
+            side: OrderSide::Buy,
+            token_type,
+            counterparty: ctx.accounts.user.key(),
+            quantity: tokens_to_sell,
+            price: order.price,
+            timestamp: clock.unix_timestamp,
+        });
+        msg!(
+            "Quick sell: sold {} tokens at {} price to order {}",
+            tokens_to_sell,
+            order.price,
+            order.id
+        );
+    }
+    drop(order_book);
+    open_orders.exit()?;
+    
+    // Move the sold tokens into escrow
+    if tokens_sold > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            to: ctx.accounts.escrow_account.to_account_info(),
+            authority: user.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, tokens_sold, wager.token_decimals)?;
+    }
+    
+    // Check slippage
+    if sol_out < min_sol_out {
+        return Err(IpredictError::SlippageExceeded.into());
+    }
+    
+    // Update stats
+    platform.total_volume_traded = platform.total_volume_traded
+        .checked_add(gross_proceeds)
+        .ok_or(IpredictError::MathOverflow)?;
+    platform.total_fees_collected = platform.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    wager.total_volume_traded = wager.total_volume_traded
+        .checked_add(gross_proceeds)
+        .ok_or(IpredictError::MathOverflow)?;
+    wager.total_fees_collected = wager.total_fees_collected
+        .checked_add(total_fees)
+        .ok_or(IpredictError::MathOverflow)?;
+    if gross_proceeds > 0 {
+        wager.last_fill_at = clock.unix_timestamp;
+    }
+    
+    // Initialize user position if needed
+    if user_position.user == Pubkey::default() {
+        user_position.user = ctx.accounts.user.key();
+        user_position.wager = wager.key();
+        user_position.bump = ctx.bumps.user_position;
+    }
+    
+    // Update user position
+    match token_type {
+        TokenType::Yes => {
+            user_position.yes_tokens_sold = user_position.yes_tokens_sold
+                .checked_add(tokens_sold)
+                .ok_or(IpredictError::MathOverflow)?;
+        }
+        TokenType::No => {
+            user_position.no_tokens_sold = user_position.no_tokens_sold
+                .checked_add(tokens_sold)
+                .ok_or(IpredictError::MathOverflow)?;
+        }
+    }
+    
+    msg!(
+        "Quick sell complete: sold {} of {} {} tokens for {} SOL",
+        tokens_sold,
+        token_amount,
+        match token_type { TokenType::Yes => "YES", TokenType::No => "NO" },
+        sol_out as f64 / LAMPORTS_PER_SOL as f64
+    );
+    
+    Ok(())
+}
\ No newline at end of file
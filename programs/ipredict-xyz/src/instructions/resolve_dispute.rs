@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus, Resolution, ResolutionProposal, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [RESOLUTION_PROPOSAL_SEED, wager.key().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, ResolutionProposal>,
+    
+    /// CHECK: Paid the proposal's rent, and both bonds if upheld
+    #[account(
+        mut,
+        address = proposal.proposer @ IpredictError::Unauthorized
+    )]
+    pub proposer: AccountInfo<'info>,
+    
+    /// CHECK: Paid both bonds if the proposal is overturned
+    #[account(
+        mut,
+        address = proposal.disputer @ IpredictError::Unauthorized
+    )]
+    pub disputer: AccountInfo<'info>,
+    
+    /// Republished with the platform's own evidence for the ruling
+    #[account(
+        mut,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump = resolution_record.bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Rule on a disputed resolution. The side the ruling agrees with gets both
+/// bonds and the resolution reward; the other side's bond is slashed.
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    resolution: Resolution,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let proposal = &ctx.accounts.proposal;
+    
+    if wager.status != WagerStatus::Disputed {
+        return Err(IpredictError::ResolutionNotDisputed.into());
+    }
+    
+    // Validate resolution
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    
+    // Update wager
+    wager.resolution = resolution;
+    wager.status = WagerStatus::Resolved;
+    
+    let bump = ctx.accounts.resolution_record.bump;
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.authority.key(),
+        resolution,
+        evidence,
+        Clock::get()?.unix_timestamp,
+        bump,
+    )?;
+    
+    // An upheld proposal gets both bonds when the proposal closes; an
+    // overturned one loses its bond to the disputer
+    let upheld = resolution == proposal.resolution;
+    let winner = if upheld {
+        &ctx.accounts.proposer
+    } else {
+        let bonds = proposal.bond
+            .checked_mul(2)
+            .ok_or(IpredictError::MathOverflow)?;
+        settlement::transfer_lamports(&proposal.to_account_info(), &ctx.accounts.disputer, bonds)?;
+        &ctx.accounts.disputer
+    };
+    
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), winner, reward)?;
+    
+    msg!(
+        "Dispute on wager {} settled as {:?}: proposal {}, {} rewarded",
+        wager.wager_id,
+        resolution,
+        if upheld { "upheld" } else { "overturned" },
+        winner.key()
+    );
+    
+    Ok(())
+}
\ No newline at end of file
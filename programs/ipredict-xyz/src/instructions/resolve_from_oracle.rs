@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::oracle::PriceFeed;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct ResolveFromOracle<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    /// CHECK: Pyth price account, checked against the wager's condition and
+    /// the Pyth program id when loaded
+    pub price_account: AccountInfo<'info>,
+    
+    /// Evidence behind the resolution, published for disputes and indexers
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    /// Receives the wager's resolution reward
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve an oracle wager from a price published at or after its
+/// resolution time. Anyone can call this; the price must be fresh, trading
+/// and tight enough to trust.
+pub fn resolve_from_oracle(ctx: Context<ResolveFromOracle>, evidence: ResolutionEvidence) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    let condition = wager.oracle_condition.ok_or(IpredictError::NotOracleWager)?;
+    if ctx.accounts.price_account.key() != condition.price_account {
+        return Err(IpredictError::InvalidOracleAccount.into());
+    }
+    
+    // Check if resolution time has passed
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    
+    // Read the aggregate price and check it can be trusted
+    let feed = PriceFeed::load(&ctx.accounts.price_account)?;
+    feed.validate(condition.exponent, wager.resolution_time, clock.unix_timestamp)?;
+    
+    // Update wager
+    wager.resolution = condition.resolve(feed.price);
+    wager.status = WagerStatus::Resolved;
+    
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.resolver.key(),
+        wager.resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.resolver, reward)?;
+    
+    msg!(
+        "Wager {} resolved as {:?} from price {} (conf {}, expo {}) published at {}",
+        wager.wager_id,
+        wager.resolution,
+        feed.price,
+        feed.conf,
+        feed.exponent,
+        feed.publish_time
+    );
+    
+    Ok(())
+}
\ No newline at end of file
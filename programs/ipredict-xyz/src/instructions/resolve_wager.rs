@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{Platform, Wager, WagerStatus, Resolution};
+use crate::state::{Platform, Wager, WagerStatus, Resolution, ResolutionArbitrator, ResolverCommittee, DaoTally, ResolutionRecord, ResolutionEvidence};
 use crate::constants::*;
 use crate::errors::IpredictError;
 
@@ -22,10 +22,40 @@ pub struct ResolveWager<'info> {
     )]
     pub wager: Account<'info, Wager>,
     
+    /// Required for committee wagers, which fall back to the platform only
+    /// once the committee's vote deadline has passed
+    #[account(address = wager.committee @ IpredictError::InvalidCommittee)]
+    pub committee: Option<Account<'info, ResolverCommittee>>,
+    
+    /// CHECK: The wager's `DaoTally` PDA, required for DAO wagers even if
+    /// nobody voted; checked in the handler
+    #[account(
+        seeds = [DAO_TALLY_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub dao_tally: Option<AccountInfo<'info>>,
+    
+    /// Evidence behind the resolution, published for disputes and indexers
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
-pub fn resolve_wager(ctx: Context<ResolveWager>, resolution: Resolution) -> Result<()> {
+pub fn resolve_wager(
+    ctx: Context<ResolveWager>,
+    resolution: Resolution,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
     let wager = &mut ctx.accounts.wager;
     
     // Check wager status
@@ -39,6 +69,42 @@ pub fn resolve_wager(ctx: Context<ResolveWager>, resolution: Resolution) -> Resu
         return Err(IpredictError::WagerNotResolvable.into());
     }
     
+    if wager.resolution_arbitrator == ResolutionArbitrator::Committee {
+        let committee = ctx.accounts.committee.as_ref().ok_or(IpredictError::InvalidCommittee)?;
+        if clock.unix_timestamp < committee.vote_deadline {
+            return Err(IpredictError::InvalidArbitrator.into());
+        }
+    }
+    
+    // DAO wagers fall back to the platform once their voting period is over,
+    // unless the vote decided them and only awaits `finalize_dao_vote`
+    if wager.resolution_arbitrator == ResolutionArbitrator::DAO {
+        if clock.unix_timestamp < wager.dao_voting_end {
+            return Err(IpredictError::InvalidArbitrator.into());
+        }
+        let dao_tally = ctx.accounts.dao_tally.as_ref().ok_or(IpredictError::InvalidArbitrator)?;
+        if !dao_tally.data_is_empty() {
+            let tally = DaoTally::try_deserialize(&mut &dao_tally.try_borrow_data()?[..])?;
+            if tally.decided(wager.dao_quorum).is_some() {
+                return Err(IpredictError::InvalidArbitrator.into());
+            }
+        }
+    }
+    
+    // Oracle wagers fall back to the platform only if the feed stays unusable
+    if wager.resolution_arbitrator == ResolutionArbitrator::Oracle
+        && clock.unix_timestamp < wager.oracle_fallback_time()
+    {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    // AI wagers fall back to the platform only if the attester stays silent
+    if wager.resolution_arbitrator == ResolutionArbitrator::AI
+        && clock.unix_timestamp < wager.attestation_fallback_time()
+    {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
     // Validate resolution
     if resolution == Resolution::Pending {
         return Err(IpredictError::InvalidResolution.into());
@@ -48,5 +114,14 @@ pub fn resolve_wager(ctx: Context<ResolveWager>, resolution: Resolution) -> Resu
     wager.resolution = resolution;
     wager.status = WagerStatus::Resolved;
     
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.authority.key(),
+        resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use crate::state::{Wager, WagerStatus, Resolution, ResolutionArbitrator, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::attestation;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct ResolveWithAttestation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    /// CHECK: Instructions sysvar, read to find the ed25519 verification
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    
+    /// Evidence behind the resolution, published for disputes and indexers
+    #[account(
+        init_if_needed,
+        payer = resolver,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+    
+    /// Receives the wager's resolution reward
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Resolve an AI-arbitrated wager with its attester's signed outcome. The
+/// transaction must verify the attester's ed25519 signature over
+/// `attestation::attestation_message` in the instruction right before this
+/// one. Anyone can submit it; the attestation must be made at or after the
+/// wager's resolution time.
+pub fn resolve_with_attestation(
+    ctx: Context<ResolveWithAttestation>,
+    resolution: Resolution,
+    timestamp: i64,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    if wager.resolution_arbitrator != ResolutionArbitrator::AI {
+        return Err(IpredictError::InvalidArbitrator.into());
+    }
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    // Check if resolution time has passed
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    
+    // Validate resolution
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    if timestamp < wager.resolution_time || timestamp > clock.unix_timestamp {
+        return Err(IpredictError::InvalidAttestation.into());
+    }
+    
+    let message = attestation::attestation_message(&wager.key(), resolution, timestamp)?;
+    attestation::verify_preceding_ed25519(&ctx.accounts.instructions, &wager.attester, &message)?;
+    
+    // Update wager
+    wager.resolution = resolution;
+    wager.status = WagerStatus::Resolved;
+    
+    ctx.accounts.resolution_record.publish(
+        wager,
+        ctx.accounts.resolver.key(),
+        resolution,
+        evidence,
+        clock.unix_timestamp,
+        ctx.bumps.resolution_record,
+    )?;
+    
+    let reward = wager.resolution_reward;
+    wager.resolution_reward = 0;
+    settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.resolver, reward)?;
+    
+    msg!(
+        "Wager {} resolved as {:?} by attester {} at {}",
+        wager.wager_id,
+        resolution,
+        wager.attester,
+        timestamp
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::{Platform, Wager, WagerStatus};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct ResumeTrading<'info> {
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Lift a halt placed in error, reopening the wager to trading and minting
+/// until its closing time.
+pub fn resume_trading(ctx: Context<ResumeTrading>) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    if !wager.is_halted() {
+        return Err(IpredictError::TradingNotHalted.into());
+    }
+    
+    let halted_at = wager.halted_at;
+    wager.halted_at = 0;
+    
+    msg!("Trading on wager {} resumed after halt at {}", wager.wager_id, halted_at);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, ResolverCommittee};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct RotateCommittee<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        address = wager.committee @ IpredictError::InvalidCommittee
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+    
+    pub member: Signer<'info>,
+}
+
+/// Approve replacing the committee's members and threshold. With no rotation
+/// pending, a member opens proposal `rotation_nonce + 1`, which collects
+/// approvals until `ROTATION_PROPOSAL_TTL` passes; until then it can only be
+/// approved as proposed or cancelled by a threshold of members with
+/// `cancel_rotation`. Once the current threshold of members approve, the new
+/// committee takes over with all resolution votes cleared.
+pub fn rotate_committee(
+    ctx: Context<RotateCommittee>,
+    new_members: Vec<Pubkey>,
+    new_threshold: u8,
+    nonce: u64,
+) -> Result<()> {
+    let committee = &mut ctx.accounts.committee;
+    
+    if ctx.accounts.wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    ResolverCommittee::validate(&new_members, new_threshold)?;
+    
+    let index = committee.member_index(&ctx.accounts.member.key())?;
+    let clock = Clock::get()?;
+    if committee.rotation_pending(clock.unix_timestamp) {
+        if nonce != committee.rotation_nonce
+            || committee.pending_members != new_members
+            || committee.pending_threshold != new_threshold
+        {
+            return Err(IpredictError::RotationProposalMismatch.into());
+        }
+    } else {
+        if nonce != committee.rotation_nonce + 1 {
+            return Err(IpredictError::RotationProposalMismatch.into());
+        }
+        let expires_at = clock.unix_timestamp
+            .checked_add(ROTATION_PROPOSAL_TTL)
+            .ok_or(IpredictError::MathOverflow)?;
+        committee.propose_rotation(new_members, new_threshold, expires_at);
+    }
+    committee.rotation_approvals |= 1 << index;
+    
+    let approvals = committee.rotation_approvals.count_ones() as u8;
+    msg!(
+        "Committee member {} approved rotation {} to {}-of-{} ({} of {} needed)",
+        ctx.accounts.member.key(),
+        nonce,
+        new_threshold,
+        committee.pending_members.len(),
+        approvals,
+        committee.threshold
+    );
+    
+    if approvals >= committee.threshold {
+        let members = std::mem::take(&mut committee.pending_members);
+        committee.rotate(members, new_threshold);
+        
+        msg!("Committee for wager {} rotated", ctx.accounts.wager.wager_id);
+    }
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::Platform;
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct SetDaoConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    pub governance_mint: Account<'info, Mint>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Set the governance token, voting period and quorum for DAO-resolved
+/// wagers created from now on. Existing wagers keep the terms they were
+/// created with.
+pub fn set_dao_config(
+    ctx: Context<SetDaoConfig>,
+    dao_voting_period: i64,
+    dao_quorum: u64,
+) -> Result<()> {
+    if dao_voting_period <= 0 || dao_quorum == 0 {
+        return Err(IpredictError::DaoNotConfigured.into());
+    }
+    
+    // Unresolved wagers must not be voidable while their vote is running
+    let platform = &mut ctx.accounts.platform;
+    if dao_voting_period >= platform.resolution_grace_period {
+        return Err(IpredictError::GracePeriodTooShort.into());
+    }
+    platform.governance_mint = ctx.accounts.governance_mint.key();
+    platform.dao_voting_period = dao_voting_period;
+    platform.dao_quorum = dao_quorum;
+    
+    msg!(
+        "DAO resolution with mint {}, {}s voting period, quorum {}",
+        platform.governance_mint,
+        dao_voting_period,
+        dao_quorum
+    );
+    
+    Ok(())
+}
\ No newline at end of file
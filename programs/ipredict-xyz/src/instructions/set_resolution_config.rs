@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::state::Platform;
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct SetResolutionConfig<'info> {
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = platform.bump,
+        constraint = platform.authority == authority.key() @ IpredictError::Unauthorized
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Set the bond, dispute window and proposer reward for future proposals and
+/// wagers, and the grace period after which future wagers can be voided if
+/// unresolved. Proposals and wagers already made keep the terms they started
+/// with.
+pub fn set_resolution_config(
+    ctx: Context<SetResolutionConfig>,
+    resolution_bond: u64,
+    dispute_window: i64,
+    proposer_reward: u64,
+    resolution_grace_period: i64,
+) -> Result<()> {
+    // A grace period shorter than the dispute window would void wagers
+    // before a disputed proposal could be finalized or ruled on
+    if dispute_window <= 0 || resolution_grace_period <= dispute_window {
+        return Err(IpredictError::InvalidDisputeWindow.into());
+    }
+    
+    // Nor may it void a wager before its resolver's fallback to the platform
+    let platform = &mut ctx.accounts.platform;
+    if resolution_grace_period <= platform.longest_fallback_delay() {
+        return Err(IpredictError::GracePeriodTooShort.into());
+    }
+    
+    platform.resolution_bond = resolution_bond;
+    platform.dispute_window = dispute_window;
+    platform.proposer_reward = proposer_reward;
+    platform.resolution_grace_period = resolution_grace_period;
+    
+    msg!(
+        "Resolution bond {} lamports, dispute window {}s, proposer reward {} lamports, grace period {}s",
+        resolution_bond,
+        dispute_window,
+        proposer_reward,
+        resolution_grace_period
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{Wager, OpenOrders, BidEscrow, TokenType};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement::{self, Collateral};
+
+#[derive(Accounts)]
+pub struct SettleFunds<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(
+        mut,
+        constraint = user_yes_account.owner == user.key(),
+        constraint = user_yes_account.mint == wager.yes_mint @ IpredictError::InvalidTokenMint
+    )]
+    pub user_yes_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = user_no_account.owner == user.key(),
+        constraint = user_no_account.mint == wager.no_mint @ IpredictError::InvalidTokenMint
+    )]
+    pub user_no_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"yes"],
+        bump
+    )]
+    pub yes_escrow: AccountInfo<'info>,
+    
+    /// CHECK: Escrow account - validated by seeds
+    #[account(
+        mut,
+        seeds = [b"escrow", wager.key().as_ref(), b"no"],
+        bump
+    )]
+    pub no_escrow: AccountInfo<'info>,
+    
+    #[account(
+        seeds = [b"yes_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"no_mint", wager.key().as_ref()],
+        bump
+    )]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Pay out the user's whole free balance: deposits no open buy order has
+/// reserved, refunds, fill proceeds and unsettled tokens
+pub fn settle_funds(ctx: Context<SettleFunds>) -> Result<()> {
+    let open_orders = &mut ctx.accounts.open_orders;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let wager_key = ctx.accounts.wager.key();
+    
+    let sol_free = open_orders.sol_free;
+    let yes_tokens_free = open_orders.yes_tokens_free;
+    let no_tokens_free = open_orders.no_tokens_free;
+    
+    // Unsettled collateral waits in the bid escrow
+    let collateral = Collateral::new(
+        &ctx.accounts.wager,
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    collateral.pay_from_bid_escrow(
+        &mut ctx.accounts.bid_escrow,
+        &ctx.accounts.bid_escrow_vault,
+        &ctx.accounts.user_collateral,
+        sol_free,
+    )?;
+    
+    // Unsettled tokens wait in the token escrows
+    if yes_tokens_free > 0 {
+        settlement::release_from_escrow(
+            &token_program,
+            &ctx.accounts.yes_escrow,
+            &ctx.accounts.yes_mint.to_account_info(),
+            &ctx.accounts.user_yes_account.to_account_info(),
+            &wager_key,
+            TokenType::Yes,
+            ctx.bumps.yes_escrow,
+            ctx.accounts.wager.token_decimals,
+            yes_tokens_free,
+        )?;
+    }
+    if no_tokens_free > 0 {
+        settlement::release_from_escrow(
+            &token_program,
+            &ctx.accounts.no_escrow,
+            &ctx.accounts.no_mint.to_account_info(),
+            &ctx.accounts.user_no_account.to_account_info(),
+            &wager_key,
+            TokenType::No,
+            ctx.bumps.no_escrow,
+            ctx.accounts.wager.token_decimals,
+            no_tokens_free,
+        )?;
+    }
+    
+    open_orders.sol_free = 0;
+    open_orders.yes_tokens_free = 0;
+    open_orders.no_tokens_free = 0;
+    
+    msg!(
+        "Settled {} lamports, {} YES and {} NO tokens",
+        sol_free,
+        yes_tokens_free,
+        no_tokens_free
+    );
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, WagerStatus, Resolution, ResolverCommittee, ResolutionRecord, ResolutionEvidence};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement;
+
+#[derive(Accounts)]
+pub struct VoteResolution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Account<'info, Wager>,
+    
+    #[account(
+        mut,
+        address = wager.committee @ IpredictError::InvalidCommittee
+    )]
+    pub committee: Account<'info, ResolverCommittee>,
+    
+    /// Required from the vote that decides the wager, to publish its evidence,
+    /// and refused from any other vote so it is only created on resolution
+    #[account(
+        init,
+        payer = member,
+        space = ResolutionRecord::SIZE,
+        seeds = [RESOLUTION_RECORD_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub resolution_record: Option<Account<'info, ResolutionRecord>>,
+    
+    /// Receives the wager's resolution reward if their vote decides it
+    #[account(mut)]
+    pub member: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Cast or change a committee member's vote. The wager resolves as soon as
+/// `threshold` members agree on one outcome, publishing the deciding
+/// member's evidence.
+pub fn vote_resolution(
+    ctx: Context<VoteResolution>,
+    resolution: Resolution,
+    evidence: ResolutionEvidence,
+) -> Result<()> {
+    let wager = &mut ctx.accounts.wager;
+    let committee = &mut ctx.accounts.committee;
+    
+    // Check wager status
+    if wager.status == WagerStatus::Resolved {
+        return Err(IpredictError::WagerAlreadyResolved.into());
+    }
+    
+    // Check if resolution time has passed and voting is still open
+    let clock = Clock::get()?;
+    if !wager.is_resolvable(&clock) {
+        return Err(IpredictError::WagerNotResolvable.into());
+    }
+    if clock.unix_timestamp >= committee.vote_deadline {
+        return Err(IpredictError::VotingClosed.into());
+    }
+    
+    // Validate resolution
+    if resolution == Resolution::Pending {
+        return Err(IpredictError::InvalidResolution.into());
+    }
+    
+    let index = committee.member_index(&ctx.accounts.member.key())?;
+    let agreeing = committee.vote(index, resolution);
+    
+    msg!(
+        "Committee member {} voted {:?} on wager {} ({} of {} needed)",
+        ctx.accounts.member.key(),
+        resolution,
+        wager.wager_id,
+        agreeing,
+        committee.threshold
+    );
+    
+    if agreeing < committee.threshold {
+        if ctx.accounts.resolution_record.is_some() {
+            return Err(IpredictError::UnexpectedResolutionRecord.into());
+        }
+    } else {
+        // Update wager
+        wager.resolution = resolution;
+        wager.status = WagerStatus::Resolved;
+        
+        let resolution_record = ctx.accounts.resolution_record
+            .as_mut()
+            .ok_or(IpredictError::ResolutionRecordRequired)?;
+        resolution_record.publish(
+            wager,
+            ctx.accounts.member.key(),
+            resolution,
+            evidence,
+            clock.unix_timestamp,
+            ctx.bumps.resolution_record.ok_or(IpredictError::ResolutionRecordRequired)?,
+        )?;
+        
+        let reward = wager.resolution_reward;
+        wager.resolution_reward = 0;
+        settlement::transfer_lamports(&wager.to_account_info(), &ctx.accounts.member, reward)?;
+        
+        msg!("Wager {} resolved as {:?} by committee vote", wager.wager_id, resolution);
+    }
+    
+    Ok(())
+}
\ No newline at end of file
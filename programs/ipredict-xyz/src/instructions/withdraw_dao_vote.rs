@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::{Wager, WagerStatus, VoteRecord};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+#[derive(Accounts)]
+pub struct WithdrawDaoVote<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        close = voter,
+        seeds = [VOTE_RECORD_SEED, wager.key().as_ref(), voter.key().as_ref()],
+        bump = vote_record.bump
+    )]
+    pub vote_record: Box<Account<'info, VoteRecord>>,
+    
+    #[account(
+        mut,
+        seeds = [DAO_VAULT_SEED, wager.key().as_ref()],
+        bump
+    )]
+    pub dao_vault: Box<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        token::mint = dao_vault.mint,
+        token::authority = voter
+    )]
+    pub voter_token_account: Box<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Unlock a voter's governance tokens once the wager is resolved or its
+/// voting period is over
+pub fn withdraw_dao_vote(ctx: Context<WithdrawDaoVote>) -> Result<()> {
+    let wager = &ctx.accounts.wager;
+    
+    let clock = Clock::get()?;
+    let voting_over = clock.unix_timestamp >= wager.dao_voting_end;
+    if wager.status != WagerStatus::Resolved && !voting_over {
+        return Err(IpredictError::VotesLocked.into());
+    }
+    
+    // Return the locked tokens; closing the record returns its rent
+    let wager_id_bytes = wager.wager_id.to_le_bytes();
+    let seeds = &[
+        WAGER_SEED,
+        wager_id_bytes.as_ref(),
+        &[wager.bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let amount = ctx.accounts.vote_record.amount;
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.dao_vault.to_account_info(),
+        to: ctx.accounts.voter_token_account.to_account_info(),
+        authority: wager.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)?;
+    
+    msg!("Voter {} unlocked {} governance tokens from wager {}", ctx.accounts.voter.key(), amount, wager.wager_id);
+    
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use crate::state::{Wager, UserPosition, OpenOrders, BidEscrow};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::settlement::Collateral;
+
+#[derive(Accounts)]
+pub struct WithdrawSol<'info> {
+    #[account(
+        seeds = [
+            WAGER_SEED,
+            wager.wager_id.to_le_bytes().as_ref()
+        ],
+        bump = wager.bump
+    )]
+    pub wager: Box<Account<'info, Wager>>,
+    
+    #[account(
+        mut,
+        seeds = [USER_POSITION_SEED, user.key().as_ref(), wager.key().as_ref()],
+        bump = user_position.bump
+    )]
+    pub user_position: Box<Account<'info, UserPosition>>,
+    
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, wager.key().as_ref(), user.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+    
+    #[account(
+        mut,
+        seeds = [BID_ESCROW_SEED, wager.key().as_ref()],
+        bump = bid_escrow.bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+    
+    #[account(
+        mut,
+        address = wager.bid_escrow_vault @ IpredictError::InvalidVault
+    )]
+    /// CHECK: Holds the bid escrow's collateral; the bid escrow itself for native SOL
+    pub bid_escrow_vault: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = wager.collateral_account(&user.key()) @ IpredictError::InvalidCollateralAccount
+    )]
+    /// CHECK: The user's wallet for native SOL, otherwise their collateral token account
+    pub user_collateral: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Withdraw part of the user's free SOL: deposits no open buy order has
+/// reserved, refunds and fill proceeds. `settle_funds` withdraws all of it.
+pub fn withdraw_sol(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
+    let user_position = &mut ctx.accounts.user_position;
+    
+    ctx.accounts.open_orders.debit_sol(amount)?;
+    user_position.total_sol_withdrawn = user_position.total_sol_withdrawn
+        .checked_add(amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    
+    let collateral = Collateral::new(
+        &ctx.accounts.wager,
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    );
+    collateral.pay_from_bid_escrow(
+        &mut ctx.accounts.bid_escrow,
+        &ctx.accounts.bid_escrow_vault,
+        &ctx.accounts.user_collateral,
+        amount,
+    )?;
+    
+    msg!("User {} withdrew {} lamports", ctx.accounts.user.key(), amount);
+    
+    Ok(())
+}
\ No newline at end of file
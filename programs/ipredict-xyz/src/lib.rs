@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 
+pub mod attestation;
 pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod oracle;
+pub mod settlement;
 pub mod state;
 
 use instructions::*;
@@ -15,11 +19,41 @@ pub mod ipredict_xyz {
     use super::*;
 
     /// Initialize the platform (one-time setup)
-    pub fn initialize_platform(ctx: Context<InitializePlatform>) -> Result<()> {
-        instructions::initialize_platform(ctx)
+    pub fn initialize_platform(
+        ctx: Context<InitializePlatform>,
+        self_trade_behavior: SelfTradeBehavior,
+    ) -> Result<()> {
+        instructions::initialize_platform(ctx, self_trade_behavior)
+    }
+
+    /// Configure bonded resolution proposals and the void grace period (authority only)
+    pub fn set_resolution_config(
+        ctx: Context<SetResolutionConfig>,
+        resolution_bond: u64,
+        dispute_window: i64,
+        proposer_reward: u64,
+        resolution_grace_period: i64,
+    ) -> Result<()> {
+        instructions::set_resolution_config(
+            ctx,
+            resolution_bond,
+            dispute_window,
+            proposer_reward,
+            resolution_grace_period,
+        )
     }
 
-    /// Create a new prediction market
+    /// Configure DAO resolution (authority only)
+    pub fn set_dao_config(
+        ctx: Context<SetDaoConfig>,
+        dao_voting_period: i64,
+        dao_quorum: u64,
+    ) -> Result<()> {
+        instructions::set_dao_config(ctx, dao_voting_period, dao_quorum)
+    }
+
+    /// Create a new prediction market, collateralized in SOL or an optional SPL mint
+    #[allow(clippy::too_many_arguments)]
     pub fn create_wager(
         ctx: Context<CreateWager>,
         name: String,
@@ -27,6 +61,12 @@ pub mod ipredict_xyz {
         opening_time: i64,
         closing_time: i64,
         resolution_time: i64,
+        metadata_uri: String,
+        collateral_per_set: u64,
+        token_decimals: u8,
+        resolution_arbitrator: ResolutionArbitrator,
+        oracle_condition: Option<OracleCondition>,
+        attester: Option<Pubkey>,
     ) -> Result<()> {
         instructions::create_wager(
             ctx,
@@ -35,23 +75,62 @@ pub mod ipredict_xyz {
             opening_time,
             closing_time,
             resolution_time,
+            metadata_uri,
+            collateral_per_set,
+            token_decimals,
+            resolution_arbitrator,
+            oracle_condition,
+            attester,
         )
     }
 
+    /// Deposit SOL as spendable balance for buy orders
+    pub fn deposit_sol(ctx: Context<DepositSol>, amount: u64) -> Result<()> {
+        instructions::deposit_sol(ctx, amount)
+    }
+
+    /// Withdraw SOL balance not reserved by open orders
+    pub fn withdraw_sol(ctx: Context<WithdrawSol>, amount: u64) -> Result<()> {
+        instructions::withdraw_sol(ctx, amount)
+    }
+
     /// Deposit SOL and mint YES/NO tokens
     pub fn deposit_and_mint(ctx: Context<DepositAndMint>, amount: u64) -> Result<()> {
         instructions::deposit_and_mint(ctx, amount)
     }
 
+    /// Burn complete YES/NO sets and withdraw their SOL
+    pub fn merge_tokens(ctx: Context<MergeTokens>, amount: u64) -> Result<()> {
+        instructions::merge_tokens(ctx, amount)
+    }
+
+    /// Create the user's open orders account for a wager
+    pub fn init_open_orders(ctx: Context<InitOpenOrders>) -> Result<()> {
+        instructions::init_open_orders(ctx)
+    }
+
     /// Place a buy or sell order
-    pub fn place_order(
-        ctx: Context<PlaceOrder>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn place_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceOrder<'info>>,
         side: OrderSide,
         token_type: TokenType,
+        order_type: OrderType,
         price: u64,
         quantity: u64,
+        expires_at: Option<i64>,
+        self_trade_behavior: Option<SelfTradeBehavior>,
     ) -> Result<()> {
-        instructions::place_order(ctx, side, token_type, price, quantity)
+        instructions::place_order(
+            ctx,
+            side,
+            token_type,
+            order_type,
+            price,
+            quantity,
+            expires_at,
+            self_trade_behavior,
+        )
     }
 
     /// Cancel an existing order
@@ -59,17 +138,41 @@ pub mod ipredict_xyz {
         instructions::cancel_order(ctx, order_id)
     }
 
+    /// Change the price or quantity of an existing order
+    pub fn amend_order(
+        ctx: Context<AmendOrder>,
+        order_id: u64,
+        new_price: u64,
+        new_quantity: u64,
+    ) -> Result<()> {
+        instructions::amend_order(ctx, order_id, new_price, new_quantity)
+    }
+
+    /// Cancel all of the user's orders, optionally filtered by side and outcome
+    pub fn cancel_all_orders(
+        ctx: Context<CancelAllOrders>,
+        side: Option<OrderSide>,
+        token_type: Option<TokenType>,
+    ) -> Result<()> {
+        instructions::cancel_all_orders(ctx, side, token_type)
+    }
+
     /// Match compatible orders
-    pub fn match_orders(
-        ctx: Context<MatchOrders>,
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, MatchOrders<'info>>,
         max_iterations: u8,
     ) -> Result<()> {
         instructions::match_orders(ctx, max_iterations)
     }
 
+    /// Pay out proceeds and refunds credited by matching
+    pub fn settle_funds(ctx: Context<SettleFunds>) -> Result<()> {
+        instructions::settle_funds(ctx)
+    }
+
     /// Quick buy tokens at market price
-    pub fn quick_buy(
-        ctx: Context<QuickBuy>,
+    pub fn quick_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, QuickBuy<'info>>,
         token_type: TokenType,
         sol_amount: u64,
         min_tokens_out: u64,
@@ -77,9 +180,162 @@ pub mod ipredict_xyz {
         instructions::quick_buy(ctx, token_type, sol_amount, min_tokens_out)
     }
 
+    /// Quick sell tokens into resting buy orders
+    pub fn quick_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, QuickSell<'info>>,
+        token_type: TokenType,
+        token_amount: u64,
+        min_sol_out: u64,
+    ) -> Result<()> {
+        instructions::quick_sell(ctx, token_type, token_amount, min_sol_out)
+    }
+
+    /// Mint complete sets, keep one side and sell the other
+    pub fn mint_and_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintAndSell<'info>>,
+        token_type_to_keep: TokenType,
+        sol_amount: u64,
+        min_price_for_other: u64,
+    ) -> Result<()> {
+        instructions::mint_and_sell(ctx, token_type_to_keep, sol_amount, min_price_for_other)
+    }
+
+    /// Remove expired orders and return their escrowed funds
+    pub fn prune_expired_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, PruneExpiredOrders<'info>>,
+        max_orders: u8,
+    ) -> Result<()> {
+        instructions::prune_expired_orders(ctx, max_orders)
+    }
+
+    /// Stop all trading and minting on a wager whose event has happened (authority only)
+    pub fn halt_trading(ctx: Context<HaltTrading>) -> Result<()> {
+        instructions::halt_trading(ctx)
+    }
+
+    /// Reopen a halted wager to trading and minting (authority only)
+    pub fn resume_trading(ctx: Context<ResumeTrading>) -> Result<()> {
+        instructions::resume_trading(ctx)
+    }
+
+    /// Resolve a halted wager before its resolution time (authority only)
+    pub fn early_resolve(
+        ctx: Context<EarlyResolve>,
+        resolution: Resolution,
+        event_time: i64,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::early_resolve(ctx, resolution, event_time, evidence)
+    }
+
     /// Resolve a wager with the outcome
-    pub fn resolve_wager(ctx: Context<ResolveWager>, resolution: Resolution) -> Result<()> {
-        instructions::resolve_wager(ctx, resolution)
+    pub fn resolve_wager(
+        ctx: Context<ResolveWager>,
+        resolution: Resolution,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::resolve_wager(ctx, resolution, evidence)
+    }
+
+    /// Resolve an oracle wager from its price feed
+    pub fn resolve_from_oracle(ctx: Context<ResolveFromOracle>, evidence: ResolutionEvidence) -> Result<()> {
+        instructions::resolve_from_oracle(ctx, evidence)
+    }
+
+    /// Resolve an AI wager with its attester's ed25519-signed outcome
+    pub fn resolve_with_attestation(
+        ctx: Context<ResolveWithAttestation>,
+        resolution: Resolution,
+        timestamp: i64,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::resolve_with_attestation(ctx, resolution, timestamp, evidence)
+    }
+
+    /// Void a wager left unresolved past the grace period
+    pub fn force_void_unresolved(ctx: Context<ForceVoidUnresolved>) -> Result<()> {
+        instructions::force_void_unresolved(ctx)
+    }
+
+    /// Hand a wager's resolution to an M-of-N committee (creator only)
+    pub fn create_committee(
+        ctx: Context<CreateCommittee>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+        vote_deadline: i64,
+    ) -> Result<()> {
+        instructions::create_committee(ctx, members, threshold, vote_deadline)
+    }
+
+    /// Vote on a wager's outcome as a committee member
+    pub fn vote_resolution(
+        ctx: Context<VoteResolution>,
+        resolution: Resolution,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::vote_resolution(ctx, resolution, evidence)
+    }
+
+    /// Propose or approve a new committee membership as a committee member
+    pub fn rotate_committee(
+        ctx: Context<RotateCommittee>,
+        new_members: Vec<Pubkey>,
+        new_threshold: u8,
+        nonce: u64,
+    ) -> Result<()> {
+        instructions::rotate_committee(ctx, new_members, new_threshold, nonce)
+    }
+
+    /// Vote to cancel the pending committee rotation as a committee member
+    pub fn cancel_rotation(ctx: Context<CancelRotation>, nonce: u64) -> Result<()> {
+        instructions::cancel_rotation(ctx, nonce)
+    }
+
+    /// Lock governance tokens behind a DAO wager's outcome
+    pub fn cast_dao_vote(
+        ctx: Context<CastDaoVote>,
+        resolution: Resolution,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::cast_dao_vote(ctx, resolution, amount)
+    }
+
+    /// Resolve a DAO wager from its tally once voting ends (anyone)
+    pub fn finalize_dao_vote(ctx: Context<FinalizeDaoVote>) -> Result<()> {
+        instructions::finalize_dao_vote(ctx)
+    }
+
+    /// Unlock governance tokens after a DAO vote
+    pub fn withdraw_dao_vote(ctx: Context<WithdrawDaoVote>) -> Result<()> {
+        instructions::withdraw_dao_vote(ctx)
+    }
+
+    /// Propose a wager's outcome with a bond
+    pub fn propose_resolution(
+        ctx: Context<ProposeResolution>,
+        resolution: Resolution,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::propose_resolution(ctx, resolution, evidence)
+    }
+
+    /// Dispute a proposed outcome with a matching bond
+    pub fn dispute_resolution(ctx: Context<DisputeResolution>) -> Result<()> {
+        instructions::dispute_resolution(ctx)
+    }
+
+    /// Resolve a wager with its undisputed proposal
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+        instructions::finalize_resolution(ctx)
+    }
+
+    /// Rule on a disputed proposal (authority only)
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        resolution: Resolution,
+        evidence: ResolutionEvidence,
+    ) -> Result<()> {
+        instructions::resolve_dispute(ctx, resolution, evidence)
     }
 
     /// Claim winnings after resolution
@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+/// Pyth oracle program that owns every genuine price account
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+/// Pyth price account magic number
+pub const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+/// Pyth account type of a price account
+pub const PYTH_PRICE_ACCOUNT: u32 = 3;
+/// Pyth aggregate status while publishers are trading
+pub const PYTH_STATUS_TRADING: u32 = 1;
+
+// Byte offsets into a Pyth price account
+const MAGIC_OFFSET: usize = 0;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPONENT_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGGREGATE_PRICE_OFFSET: usize = 208;
+const AGGREGATE_CONF_OFFSET: usize = 216;
+const AGGREGATE_STATUS_OFFSET: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+/// The aggregate price read from a Pyth-format price account. `price` and
+/// `conf` are integers scaled by `10^exponent`.
+#[derive(Debug)]
+pub struct PriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub status: u32,
+}
+
+impl PriceFeed {
+    pub fn load(account: &AccountInfo) -> Result<Self> {
+        // Anyone can write a Pyth-shaped account; only Pyth's are trusted
+        if account.owner != &PYTH_PROGRAM_ID {
+            return Err(IpredictError::InvalidOracleOwner.into());
+        }
+        
+        let data = account.try_borrow_data()?;
+        if data.len() < PRICE_ACCOUNT_MIN_LEN
+            || read_u32(&data, MAGIC_OFFSET) != PYTH_MAGIC
+            || read_u32(&data, ACCOUNT_TYPE_OFFSET) != PYTH_PRICE_ACCOUNT
+        {
+            return Err(IpredictError::InvalidOracleAccount.into());
+        }
+        
+        Ok(Self {
+            price: read_u64(&data, AGGREGATE_PRICE_OFFSET) as i64,
+            conf: read_u64(&data, AGGREGATE_CONF_OFFSET),
+            exponent: read_u32(&data, EXPONENT_OFFSET) as i32,
+            publish_time: read_u64(&data, TIMESTAMP_OFFSET) as i64,
+            status: read_u32(&data, AGGREGATE_STATUS_OFFSET),
+        })
+    }
+    
+    /// Whether the confidence interval is within `max_conf_bps` of the price
+    pub fn is_confident(&self, max_conf_bps: u64) -> bool {
+        (self.conf as u128) * (BPS_DIVISOR as u128)
+            <= (self.price.unsigned_abs() as u128) * (max_conf_bps as u128)
+    }
+    
+    /// Check the price can settle a wager resolving at `resolution_time`
+    /// with strikes scaled by `10^exponent`: it must use that exponent and be
+    /// trading, published since then, fresh and tight enough to trust
+    pub fn validate(&self, exponent: i32, resolution_time: i64, now: i64) -> Result<()> {
+        if self.exponent != exponent {
+            return Err(IpredictError::OracleExponentMismatch.into());
+        }
+        if self.status != PYTH_STATUS_TRADING {
+            return Err(IpredictError::InvalidOracleAccount.into());
+        }
+        if self.publish_time < resolution_time
+            || now.saturating_sub(self.publish_time) > MAX_ORACLE_STALENESS
+        {
+            return Err(IpredictError::StaleOraclePrice.into());
+        }
+        if !self.is_confident(MAX_ORACLE_CONFIDENCE_BPS) {
+            return Err(IpredictError::OracleConfidenceTooWide.into());
+        }
+        Ok(())
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{OracleComparator, OracleCondition, Resolution};
+    
+    const RESOLUTION_TIME: i64 = 1_700_000_000;
+    
+    fn price_account(price: i64, conf: u64, exponent: i32, publish_time: i64, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; PRICE_ACCOUNT_MIN_LEN];
+        data[MAGIC_OFFSET..MAGIC_OFFSET + 4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[ACCOUNT_TYPE_OFFSET..ACCOUNT_TYPE_OFFSET + 4].copy_from_slice(&PYTH_PRICE_ACCOUNT.to_le_bytes());
+        data[20..24].copy_from_slice(&exponent.to_le_bytes());
+        data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[216..224].copy_from_slice(&conf.to_le_bytes());
+        data[224..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+    
+    fn load(mut data: Vec<u8>, owner: Pubkey) -> Result<PriceFeed> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        PriceFeed::load(&account)
+    }
+    
+    fn feed(price: i64, conf: u64, publish_time: i64, status: u32) -> PriceFeed {
+        PriceFeed { price, conf, exponent: -8, publish_time, status }
+    }
+    
+    #[test]
+    fn reads_aggregate_at_pyth_offsets() {
+        let data = price_account(6_512_345_678_900, 1_234_567, -8, RESOLUTION_TIME + 5, PYTH_STATUS_TRADING);
+        let feed = load(data, PYTH_PROGRAM_ID).unwrap();
+        assert_eq!(feed.exponent, -8);
+        assert_eq!(feed.publish_time, RESOLUTION_TIME + 5);
+        assert_eq!(feed.price, 6_512_345_678_900);
+        assert_eq!(feed.conf, 1_234_567);
+        assert_eq!(feed.status, PYTH_STATUS_TRADING);
+    }
+    
+    #[test]
+    fn reads_negative_exponent_and_price() {
+        let data = price_account(-42_000, 10, -5, RESOLUTION_TIME, PYTH_STATUS_TRADING);
+        let feed = load(data, PYTH_PROGRAM_ID).unwrap();
+        assert_eq!(feed.exponent, -5);
+        assert_eq!(feed.price, -42_000);
+        assert!(feed.is_confident(MAX_ORACLE_CONFIDENCE_BPS));
+    }
+    
+    #[test]
+    fn rejects_short_account() {
+        let mut data = price_account(100, 1, -8, RESOLUTION_TIME, PYTH_STATUS_TRADING);
+        data.truncate(PRICE_ACCOUNT_MIN_LEN - 1);
+        assert_eq!(load(data, PYTH_PROGRAM_ID).unwrap_err(), IpredictError::InvalidOracleAccount.into());
+    }
+    
+    #[test]
+    fn rejects_other_account_types() {
+        let mut data = price_account(100, 1, -8, RESOLUTION_TIME, PYTH_STATUS_TRADING);
+        data[MAGIC_OFFSET] ^= 1;
+        assert_eq!(load(data, PYTH_PROGRAM_ID).unwrap_err(), IpredictError::InvalidOracleAccount.into());
+        
+        let mut data = price_account(100, 1, -8, RESOLUTION_TIME, PYTH_STATUS_TRADING);
+        data[ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(load(data, PYTH_PROGRAM_ID).unwrap_err(), IpredictError::InvalidOracleAccount.into());
+    }
+    
+    #[test]
+    fn rejects_accounts_pyth_does_not_own() {
+        let data = price_account(100, 1, -8, RESOLUTION_TIME, PYTH_STATUS_TRADING);
+        assert_eq!(load(data, Pubkey::new_unique()).unwrap_err(), IpredictError::InvalidOracleOwner.into());
+    }
+    
+    #[test]
+    fn rejects_price_not_trading() {
+        let now = RESOLUTION_TIME + 10;
+        assert!(feed(100_000, 10, now, PYTH_STATUS_TRADING).validate(-8, RESOLUTION_TIME, now).is_ok());
+        for status in [0, 2, 3] {
+            assert_eq!(
+                feed(100_000, 10, now, status).validate(-8, RESOLUTION_TIME, now).unwrap_err(),
+                IpredictError::InvalidOracleAccount.into()
+            );
+        }
+    }
+    
+    #[test]
+    fn rejects_stale_price() {
+        let now = RESOLUTION_TIME + 1_000;
+        let stale = IpredictError::StaleOraclePrice.into();
+        
+        // Published before the wager's resolution time
+        assert_eq!(feed(100_000, 10, RESOLUTION_TIME - 1, 1).validate(-8, RESOLUTION_TIME, RESOLUTION_TIME).unwrap_err(), stale);
+        // Older than the staleness limit
+        assert_eq!(feed(100_000, 10, now - MAX_ORACLE_STALENESS - 1, 1).validate(-8, RESOLUTION_TIME, now).unwrap_err(), stale);
+        assert!(feed(100_000, 10, now - MAX_ORACLE_STALENESS, 1).validate(-8, RESOLUTION_TIME, now).is_ok());
+    }
+    
+    #[test]
+    fn rejects_wide_confidence() {
+        let now = RESOLUTION_TIME;
+        // 1% of 100_000 is the widest interval accepted
+        assert!(feed(100_000, 1_000, now, 1).validate(-8, RESOLUTION_TIME, now).is_ok());
+        assert!(feed(-100_000, 1_000, now, 1).validate(-8, RESOLUTION_TIME, now).is_ok());
+        assert_eq!(
+            feed(100_000, 1_001, now, 1).validate(-8, RESOLUTION_TIME, now).unwrap_err(),
+            IpredictError::OracleConfidenceTooWide.into()
+        );
+        assert_eq!(
+            feed(0, 1, now, 1).validate(-8, RESOLUTION_TIME, now).unwrap_err(),
+            IpredictError::OracleConfidenceTooWide.into()
+        );
+    }
+    
+    #[test]
+    fn rejects_unexpected_exponent() {
+        let now = RESOLUTION_TIME;
+        let feed = feed(100_000, 10, now, PYTH_STATUS_TRADING);
+        assert!(feed.validate(-8, RESOLUTION_TIME, now).is_ok());
+        for exponent in [-6, -9, 0, 8] {
+            assert_eq!(
+                feed.validate(exponent, RESOLUTION_TIME, now).unwrap_err(),
+                IpredictError::OracleExponentMismatch.into()
+            );
+        }
+    }
+    
+    #[test]
+    fn resolves_condition_against_strikes() {
+        let condition = |comparator, strike, upper_strike| OracleCondition {
+            price_account: PYTH_PROGRAM_ID,
+            comparator,
+            strike,
+            upper_strike,
+            exponent: -8,
+        };
+        
+        let above = condition(OracleComparator::GreaterThan, 100, 0);
+        assert_eq!(above.resolve(101), Resolution::YesWon);
+        assert_eq!(above.resolve(100), Resolution::NoWon);
+        
+        let below = condition(OracleComparator::LessThan, -100, 0);
+        assert_eq!(below.resolve(-101), Resolution::YesWon);
+        assert_eq!(below.resolve(-100), Resolution::NoWon);
+        
+        let between = condition(OracleComparator::Between, 90, 110);
+        assert_eq!(between.resolve(90), Resolution::YesWon);
+        assert_eq!(between.resolve(110), Resolution::YesWon);
+        assert_eq!(between.resolve(89), Resolution::NoWon);
+        assert_eq!(between.resolve(111), Resolution::NoWon);
+    }
+}
\ No newline at end of file
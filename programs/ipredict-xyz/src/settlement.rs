@@ -0,0 +1,783 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Transfer};
+use anchor_spl::token_interface::{self, Burn, MintTo, TransferChecked};
+use crate::state::{BidEscrow, OpenOrders, Order, OrderBook, OrderSide, TokenType, Wager};
+use crate::constants::*;
+use crate::errors::IpredictError;
+use crate::events::OrderFilled;
+
+/// Trading fee split for a given notional amount
+pub struct Fees {
+    pub total: u64,
+    pub platform: u64,
+    pub creator: u64,
+}
+
+impl Fees {
+    pub fn on(amount: u64) -> Result<Self> {
+        let total = amount
+            .checked_mul(TOTAL_FEE_BPS as u64)
+            .ok_or(IpredictError::MathOverflow)?
+            .checked_div(BPS_DIVISOR)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        let platform = total
+            .checked_mul(PLATFORM_FEE_BPS as u64)
+            .ok_or(IpredictError::MathOverflow)?
+            .checked_div(TOTAL_FEE_BPS as u64)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        Ok(Self {
+            total,
+            platform,
+            creator: total.saturating_sub(platform),
+        })
+    }
+}
+
+/// Prices a YES bid and a NO bid pay when filled together by minting a set.
+/// Their surplus over `set_value` is split between them, the NO bid getting
+/// the odd lamport, so the two prices always add up to `set_value`.
+pub fn mint_prices(yes_bid: u64, no_bid: u64, set_value: u64) -> Result<(u64, u64)> {
+    let surplus = yes_bid
+        .checked_add(no_bid)
+        .ok_or(IpredictError::MathOverflow)?
+        .checked_sub(set_value)
+        .ok_or(IpredictError::MathOverflow)?;
+    let yes_price = yes_bid - surplus / 2;
+    Ok((yes_price, set_value - yes_price))
+}
+
+/// Prices a YES ask and a NO ask receive when filled together by burning a
+/// set. The discount under `set_value` is split between them, the NO ask
+/// getting the odd lamport, so the two prices always add up to `set_value`.
+pub fn burn_prices(yes_ask: u64, no_ask: u64, set_value: u64) -> Result<(u64, u64)> {
+    let surplus = set_value
+        .checked_sub(yes_ask)
+        .and_then(|rest| rest.checked_sub(no_ask))
+        .ok_or(IpredictError::MathOverflow)?;
+    let yes_price = yes_ask + surplus / 2;
+    Ok((yes_price, set_value - yes_price))
+}
+
+/// Move outcome tokens out of a wager's escrow PDA
+#[allow(clippy::too_many_arguments)]
+pub fn release_from_escrow<'info>(
+    token_program: &AccountInfo<'info>,
+    escrow: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    wager_key: &Pubkey,
+    token_type: TokenType,
+    escrow_bump: u8,
+    decimals: u8,
+    amount: u64,
+) -> Result<()> {
+    let escrow_seeds = &[
+        ESCROW_SEED,
+        wager_key.as_ref(),
+        token_type.to_seed(),
+        &[escrow_bump],
+    ];
+    let escrow_signer = &[&escrow_seeds[..]];
+    
+    let cpi_accounts = TransferChecked {
+        from: escrow.clone(),
+        mint: mint.clone(),
+        to: to.clone(),
+        authority: escrow.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, escrow_signer);
+    token_interface::transfer_checked(cpi_ctx, amount, decimals)
+}
+
+/// Move lamports out of an account owned by this program
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(IpredictError::InsufficientBalance)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(IpredictError::MathOverflow)?;
+    Ok(())
+}
+
+/// Moves a wager's collateral. Native SOL wagers hold lamports in the vault PDA
+/// and the bid escrow itself; SPL wagers hold `collateral_mint` tokens in
+/// token accounts owned by the wager PDA.
+pub struct Collateral<'info> {
+    pub mint: Pubkey,
+    pub wager: AccountInfo<'info>,
+    pub wager_id: u64,
+    pub wager_bump: u8,
+    pub vault_bump: u8,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> Collateral<'info> {
+    pub fn new(
+        wager: &Account<'info, Wager>,
+        token_program: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+    ) -> Self {
+        Self {
+            mint: wager.collateral_mint,
+            wager: wager.to_account_info(),
+            wager_id: wager.wager_id,
+            wager_bump: wager.bump,
+            vault_bump: wager.vault_bump,
+            token_program,
+            system_program,
+        }
+    }
+    
+    pub fn is_native(&self) -> bool {
+        self.mint == Pubkey::default()
+    }
+    
+    /// Transfer out of a token account owned by the wager PDA
+    fn transfer_from_wager(&self, from: &AccountInfo<'info>, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let wager_id_bytes = self.wager_id.to_le_bytes();
+        let seeds = &[
+            WAGER_SEED,
+            wager_id_bytes.as_ref(),
+            &[self.wager_bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: from.clone(),
+            to: to.clone(),
+            authority: self.wager.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, amount)
+    }
+    
+    /// Move collateral the user signed for: lamports from their wallet, or
+    /// tokens from their collateral token account
+    pub fn deposit(
+        &self,
+        from: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        if !authority.is_signer {
+            return Err(IpredictError::Unauthorized.into());
+        }
+        
+        if self.is_native() {
+            let cpi_context = CpiContext::new(
+                self.system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: authority.clone(),
+                    to: to.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, amount)
+        } else {
+            let cpi_accounts = Transfer {
+                from: from.clone(),
+                to: to.clone(),
+                authority: authority.clone(),
+            };
+            let cpi_ctx = CpiContext::new(self.token_program.clone(), cpi_accounts);
+            token::transfer(cpi_ctx, amount)
+        }
+    }
+    
+    /// Collateral the vault holds for token holders, excluding a native
+    /// vault's rent-exempt reserve
+    pub fn vault_balance(&self, vault: &AccountInfo<'info>) -> Result<u64> {
+        if self.is_native() {
+            let reserve = Rent::get()?.minimum_balance(0);
+            return Ok(vault.lamports().saturating_sub(reserve));
+        }
+        let data = vault.try_borrow_data()?;
+        Ok(token::TokenAccount::try_deserialize(&mut &data[..])?.amount)
+    }
+    
+    /// Pay collateral out of the wager's vault
+    pub fn pay_from_vault(&self, vault: &AccountInfo<'info>, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        if !self.is_native() {
+            return self.transfer_from_wager(vault, to, amount);
+        }
+        
+        let wager_key = self.wager.key();
+        let vault_seeds = &[
+            VAULT_SEED,
+            wager_key.as_ref(),
+            &[self.vault_bump],
+        ];
+        let vault_signer = &[&vault_seeds[..]];
+        let cpi_context = CpiContext::new_with_signer(
+            self.system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: vault.clone(),
+                to: to.clone(),
+            },
+            vault_signer,
+        );
+        anchor_lang::system_program::transfer(cpi_context, amount)
+    }
+    
+    /// Lock a buyer's collateral in the wager's bid escrow
+    pub fn lock_in_bid_escrow(
+        &self,
+        from: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        bid_escrow: &mut Account<'info, BidEscrow>,
+        bid_escrow_vault: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        self.deposit(from, authority, bid_escrow_vault, amount)?;
+        bid_escrow.total_locked = bid_escrow.total_locked
+            .checked_add(amount)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Release locked collateral from the wager's bid escrow
+    pub fn pay_from_bid_escrow(
+        &self,
+        bid_escrow: &mut Account<'info, BidEscrow>,
+        bid_escrow_vault: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        
+        bid_escrow.total_locked = bid_escrow.total_locked
+            .checked_sub(amount)
+            .ok_or(IpredictError::InsufficientBalance)?;
+        
+        if !self.is_native() {
+            return self.transfer_from_wager(bid_escrow_vault, to, amount);
+        }
+        
+        transfer_lamports(&bid_escrow.to_account_info(), to, amount)
+    }
+    
+    /// Move collateral from the vault into the bid escrow
+    pub fn vault_to_bid_escrow(
+        &self,
+        vault: &AccountInfo<'info>,
+        bid_escrow: &mut Account<'info, BidEscrow>,
+        bid_escrow_vault: &AccountInfo<'info>,
+        amount: u64,
+    ) -> Result<()> {
+        self.pay_from_vault(vault, bid_escrow_vault, amount)?;
+        bid_escrow.total_locked = bid_escrow.total_locked
+            .checked_add(amount)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Traders' `OpenOrders` accounts for one wager, found in `remaining_accounts`
+/// and credited in memory until `exit` writes them back
+pub struct OpenOrdersSet<'info> {
+    entries: Vec<(AccountInfo<'info>, OpenOrders)>,
+}
+
+impl<'info> OpenOrdersSet<'info> {
+    pub fn load(wager: &Pubkey, accounts: &[AccountInfo<'info>]) -> Result<Self> {
+        let mut set = Self { entries: Vec::new() };
+        for account in accounts {
+            set.insert(wager, account)?;
+        }
+        Ok(set)
+    }
+    
+    /// Add one account if it is an `OpenOrders` for `wager` not already held
+    pub fn insert(&mut self, wager: &Pubkey, account: &AccountInfo<'info>) -> Result<()> {
+        if account.owner != &crate::ID || self.entries.iter().any(|(info, _)| info.key == account.key) {
+            return Ok(());
+        }
+        
+        let open_orders = {
+            let data = account.try_borrow_data()?;
+            if data.len() < 8 || data[..8] != OpenOrders::DISCRIMINATOR {
+                return Ok(());
+            }
+            OpenOrders::try_deserialize(&mut &data[..])?
+        };
+        if open_orders.wager != *wager {
+            return Ok(());
+        }
+        if !account.is_writable {
+            return Err(IpredictError::SettlementAccountMissing.into());
+        }
+        
+        self.entries.push((account.clone(), open_orders));
+        Ok(())
+    }
+    
+    pub fn get_mut(&mut self, owner: &Pubkey) -> Result<&mut OpenOrders> {
+        self.entries
+            .iter_mut()
+            .find(|(_, open_orders)| open_orders.owner == *owner)
+            .map(|(_, open_orders)| open_orders)
+            .ok_or_else(|| IpredictError::SettlementAccountMissing.into())
+    }
+    
+    pub fn credit_sol(&mut self, owner: &Pubkey, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        self.get_mut(owner)?.credit_sol(amount)
+    }
+    
+    pub fn debit_sol(&mut self, owner: &Pubkey, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        self.get_mut(owner)?.debit_sol(amount)
+    }
+    
+    pub fn credit_tokens(&mut self, owner: &Pubkey, token_type: TokenType, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        self.get_mut(owner)?.credit_tokens(token_type, amount)
+    }
+    
+    /// Stop tracking an order that has been filled or cancelled
+    pub fn remove_order(&mut self, order: &Order) -> Result<()> {
+        self.get_mut(&order.owner)?.remove_order(order.id);
+        Ok(())
+    }
+    
+    /// Write every credited account back to its data
+    pub fn exit(&self) -> Result<()> {
+        for (info, open_orders) in &self.entries {
+            let mut data = info.try_borrow_mut_data()?;
+            open_orders.try_serialize(&mut &mut data[..])?;
+        }
+        Ok(())
+    }
+}
+
+/// Accounts needed to settle fills between resting orders. Proceeds and
+/// refunds are credited to each owner's `OpenOrders`, so no trader has to be
+/// part of the transaction.
+pub struct Settlement<'a, 'info> {
+    pub wager: &'a mut Account<'info, Wager>,
+    pub yes_mint: AccountInfo<'info>,
+    pub no_mint: AccountInfo<'info>,
+    pub yes_escrow: AccountInfo<'info>,
+    pub yes_escrow_bump: u8,
+    pub no_escrow: AccountInfo<'info>,
+    pub no_escrow_bump: u8,
+    pub bid_escrow: &'a mut Account<'info, BidEscrow>,
+    pub bid_escrow_vault: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+    pub platform_fee_recipient: AccountInfo<'info>,
+    pub creator_fee_recipient: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub collateral: Collateral<'info>,
+    pub open_orders: OpenOrdersSet<'info>,
+    pub volume: u64,
+    pub fees: u64,
+    /// Timestamp stamped on every fill
+    pub now: i64,
+}
+
+impl<'a, 'info> Settlement<'a, 'info> {
+    fn mint(&self, token_type: TokenType) -> &AccountInfo<'info> {
+        match token_type {
+            TokenType::Yes => &self.yes_mint,
+            TokenType::No => &self.no_mint,
+        }
+    }
+    
+    fn escrow(&self, token_type: TokenType) -> (&AccountInfo<'info>, u8) {
+        match token_type {
+            TokenType::Yes => (&self.yes_escrow, self.yes_escrow_bump),
+            TokenType::No => (&self.no_escrow, self.no_escrow_bump),
+        }
+    }
+    
+    fn record(&mut self, volume: u64, fees: u64) -> Result<()> {
+        self.volume = self.volume
+            .checked_add(volume)
+            .ok_or(IpredictError::MathOverflow)?;
+        self.fees = self.fees
+            .checked_add(fees)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(())
+    }
+    
+    fn emit_fill(&self, order: &Order, counterparty: &Order, quantity: u64, price: u64) {
+        emit!(OrderFilled {
+            wager: self.wager.key(),
+            order_id: order.id,
+            owner: order.owner,
+            side: order.side(),
+            token_type: order.token_type(),
+            counterparty: counterparty.owner,
+            quantity,
+            price,
+            timestamp: self.now,
+        });
+    }
+    
+    /// Cancel `quantity` of an order, crediting what it locked back to its owner
+    pub fn refund(&mut self, order: &Order, quantity: u64) -> Result<()> {
+        if quantity == 0 {
+            return Ok(());
+        }
+        
+        match order.side() {
+            OrderSide::Buy => {
+                let refund = quantity
+                    .checked_mul(order.price)
+                    .ok_or(IpredictError::MathOverflow)?;
+                self.open_orders.credit_sol(&order.owner, refund)?;
+            }
+            OrderSide::Sell => {
+                self.open_orders.credit_tokens(&order.owner, order.token_type(), quantity)?;
+            }
+        }
+        
+        msg!("Self-trade prevention cancelled {} of order {}", quantity, order.id);
+        
+        Ok(())
+    }
+    
+    /// Apply self-trade prevention to two crossing orders from the same
+    /// owner, following the newer order's behavior. Returns the quantities
+    /// cancelled from (newer, older).
+    pub fn prevent_self_trade(&mut self, newer: &Order, older: &Order) -> Result<(u64, u64)> {
+        let (newer_cancelled, older_cancelled) = newer.self_trade_behavior().cancel_quantities(newer, older);
+        self.refund(newer, newer_cancelled)?;
+        self.refund(older, older_cancelled)?;
+        Ok((newer_cancelled, older_cancelled))
+    }
+    
+    /// Settle a bid against an ask for the same outcome at the midpoint price
+    pub fn fill(&mut self, token_type: TokenType, bid: &Order, ask: &Order, quantity: u64) -> Result<()> {
+        let execution_price = bid.price
+            .checked_add(ask.price)
+            .ok_or(IpredictError::MathOverflow)?
+            .checked_div(2)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        let trade_amount = quantity
+            .checked_mul(execution_price)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        let fees = Fees::on(trade_amount)?;
+        let seller_receives = trade_amount.saturating_sub(fees.total);
+        
+        // The buyer locked their limit price, so any price improvement goes back to them
+        let buyer_refund = bid.price
+            .checked_sub(execution_price)
+            .ok_or(IpredictError::MathOverflow)?
+            .checked_mul(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        // The seller's escrowed tokens now belong to the buyer
+        self.open_orders.credit_tokens(&bid.owner, token_type, quantity)?;
+        
+        // Fees leave the buyer's locked collateral; the rest is owed to the seller
+        self.collateral.pay_from_bid_escrow(self.bid_escrow, &self.bid_escrow_vault, &self.platform_fee_recipient, fees.platform)?;
+        self.collateral.pay_from_bid_escrow(self.bid_escrow, &self.bid_escrow_vault, &self.creator_fee_recipient, fees.creator)?;
+        self.open_orders.credit_sol(&ask.owner, seller_receives)?;
+        self.open_orders.credit_sol(&bid.owner, buyer_refund)?;
+        
+        self.record(trade_amount, fees.total)?;
+        self.emit_fill(bid, ask, quantity, execution_price);
+        self.emit_fill(ask, bid, quantity, execution_price);
+        
+        msg!(
+            "Matched {} orders {} -> {}: {} tokens at {} price, fee: {}",
+            match token_type { TokenType::Yes => "YES", TokenType::No => "NO" },
+            ask.id,
+            bid.id,
+            quantity,
+            execution_price,
+            fees.total
+        );
+        
+        Ok(())
+    }
+    
+    /// Settle a YES bid against a NO bid by minting complete sets into the
+    /// escrows. Like `deposit_and_mint`, this carries no trading fee; the
+    /// surplus over the set value is split between both bidders.
+    pub fn fill_mint(&mut self, yes_bid: &Order, no_bid: &Order, quantity: u64, set_value: u64) -> Result<()> {
+        let (yes_price, no_price) = mint_prices(yes_bid.price, no_bid.price, set_value)?;
+        
+        let collateral = quantity
+            .checked_mul(set_value)
+            .ok_or(IpredictError::MathOverflow)?;
+        let yes_refund = (yes_bid.price - yes_price)
+            .checked_mul(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        let no_refund = (no_bid.price - no_price)
+            .checked_mul(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        // Back the new set with the bidders' locked collateral
+        self.collateral.pay_from_bid_escrow(self.bid_escrow, &self.bid_escrow_vault, &self.vault, collateral)?;
+        self.open_orders.credit_sol(&yes_bid.owner, yes_refund)?;
+        self.open_orders.credit_sol(&no_bid.owner, no_refund)?;
+        
+        let wager_id_bytes = self.wager.wager_id.to_le_bytes();
+        let seeds = &[
+            WAGER_SEED,
+            wager_id_bytes.as_ref(),
+            &[self.wager.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        for token_type in [TokenType::Yes, TokenType::No] {
+            let cpi_accounts = MintTo {
+                mint: self.mint(token_type).clone(),
+                to: self.escrow(token_type).0.clone(),
+                authority: self.wager.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, signer);
+            token_interface::mint_to(cpi_ctx, quantity)?;
+        }
+        self.open_orders.credit_tokens(&yes_bid.owner, TokenType::Yes, quantity)?;
+        self.open_orders.credit_tokens(&no_bid.owner, TokenType::No, quantity)?;
+        
+        self.wager.total_yes_tokens = self.wager.total_yes_tokens
+            .checked_add(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        self.wager.total_no_tokens = self.wager.total_no_tokens
+            .checked_add(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        self.wager.total_sol_deposited = self.wager.total_sol_deposited
+            .checked_add(collateral)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        self.record(collateral, 0)?;
+        self.emit_fill(yes_bid, no_bid, quantity, yes_price);
+        self.emit_fill(no_bid, yes_bid, quantity, no_price);
+        
+        msg!(
+            "Minted {} sets for YES order {} at {} and NO order {} at {}",
+            quantity,
+            yes_bid.id,
+            yes_price,
+            no_bid.id,
+            no_price
+        );
+        
+        Ok(())
+    }
+    
+    /// Settle a YES ask against a NO ask by burning complete sets out of the
+    /// escrows and moving their collateral from the vault to the sellers,
+    /// fee-free like a merge. The discount under the set value is split
+    /// between both sellers.
+    pub fn fill_burn(&mut self, yes_ask: &Order, no_ask: &Order, quantity: u64, set_value: u64) -> Result<()> {
+        let (yes_price, no_price) = burn_prices(yes_ask.price, no_ask.price, set_value)?;
+        
+        let yes_proceeds = yes_price
+            .checked_mul(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        let no_proceeds = no_price
+            .checked_mul(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        let collateral = yes_proceeds
+            .checked_add(no_proceeds)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        let wager_key = self.wager.key();
+        for token_type in [TokenType::Yes, TokenType::No] {
+            let (escrow, escrow_bump) = self.escrow(token_type);
+            let escrow_seeds = &[
+                ESCROW_SEED,
+                wager_key.as_ref(),
+                token_type.to_seed(),
+                &[escrow_bump],
+            ];
+            let escrow_signer = &[&escrow_seeds[..]];
+            
+            let cpi_accounts = Burn {
+                mint: self.mint(token_type).clone(),
+                from: escrow.clone(),
+                authority: escrow.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(self.token_program.clone(), cpi_accounts, escrow_signer);
+            token_interface::burn(cpi_ctx, quantity)?;
+        }
+        
+        // Release the collateral behind the burned sets into the bid escrow
+        self.collateral.vault_to_bid_escrow(&self.vault, self.bid_escrow, &self.bid_escrow_vault, collateral)?;
+        self.open_orders.credit_sol(&yes_ask.owner, yes_proceeds)?;
+        self.open_orders.credit_sol(&no_ask.owner, no_proceeds)?;
+        
+        self.wager.total_yes_tokens = self.wager.total_yes_tokens
+            .checked_sub(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        self.wager.total_no_tokens = self.wager.total_no_tokens
+            .checked_sub(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        
+        self.record(collateral, 0)?;
+        self.emit_fill(yes_ask, no_ask, quantity, yes_price);
+        self.emit_fill(no_ask, yes_ask, quantity, no_price);
+        
+        msg!(
+            "Burned {} sets for YES order {} at {} and NO order {} at {}",
+            quantity,
+            yes_ask.id,
+            yes_price,
+            no_ask.id,
+            no_price
+        );
+        
+        Ok(())
+    }
+    
+    /// Match an incoming order against the resting book until it stops
+    /// crossing. The taker's SOL or tokens must already be locked in escrow,
+    /// exactly as for a resting order. Returns the quantity of the taker
+    /// cancelled by self-trade prevention.
+    pub fn take(&mut self, order_book: &mut OrderBook, taker: &mut Order, set_value: u64, now: i64) -> Result<u64> {
+        let mut self_trade_cancelled = 0u64;
+        
+        while !taker.is_filled() {
+            let (side, token_type) = match order_book.best_match(taker, set_value, now) {
+                Some(book) => book,
+                None => break,
+            };
+            let (index, maker) = order_book.best_order(side, token_type, now).ok_or(IpredictError::OrderNotFound)?;
+            
+            // The taker is always the newer of the two orders
+            if maker.owner == taker.owner && maker.token_type() == taker.token_type() {
+                let (taker_cancelled, maker_cancelled) = self.prevent_self_trade(taker, &maker)?;
+                if order_book.tree_mut(side, token_type).reduce(index, maker_cancelled)? {
+                    self.open_orders.remove_order(&maker)?;
+                }
+                taker.quantity = taker.quantity
+                    .checked_sub(taker_cancelled)
+                    .ok_or(IpredictError::MathOverflow)?;
+                self_trade_cancelled = self_trade_cancelled
+                    .checked_add(taker_cancelled)
+                    .ok_or(IpredictError::MathOverflow)?;
+                continue;
+            }
+            
+            let quantity = taker.remaining_quantity().min(maker.remaining_quantity());
+            
+            let taker_is_yes = taker.token_type() == TokenType::Yes;
+            match (taker.side(), side) {
+                (OrderSide::Buy, OrderSide::Sell) => self.fill(token_type, taker, &maker, quantity)?,
+                (OrderSide::Sell, OrderSide::Buy) => self.fill(token_type, &maker, taker, quantity)?,
+                (OrderSide::Buy, OrderSide::Buy) if taker_is_yes => self.fill_mint(taker, &maker, quantity, set_value)?,
+                (OrderSide::Buy, OrderSide::Buy) => self.fill_mint(&maker, taker, quantity, set_value)?,
+                (OrderSide::Sell, OrderSide::Sell) if taker_is_yes => self.fill_burn(taker, &maker, quantity, set_value)?,
+                (OrderSide::Sell, OrderSide::Sell) => self.fill_burn(&maker, taker, quantity, set_value)?,
+            }
+            
+            if order_book.tree_mut(side, token_type).fill(index, quantity)? {
+                self.open_orders.remove_order(&maker)?;
+            }
+            taker.filled_quantity = taker.filled_quantity
+                .checked_add(quantity)
+                .ok_or(IpredictError::MathOverflow)?;
+        }
+        
+        Ok(self_trade_cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn mint_splits_even_surplus() {
+        // 60 + 50 over a set of 100 leaves 10 to share
+        assert_eq!(mint_prices(60, 50, 100).unwrap(), (55, 45));
+    }
+    
+    #[test]
+    fn mint_at_exactly_set_value() {
+        assert_eq!(mint_prices(60, 40, 100).unwrap(), (60, 40));
+        assert_eq!(mint_prices(100, 0, 100).unwrap(), (100, 0));
+    }
+    
+    #[test]
+    fn mint_gives_odd_lamport_to_no() {
+        let (yes_price, no_price) = mint_prices(61, 50, 100).unwrap();
+        assert_eq!((yes_price, no_price), (56, 44));
+        
+        // Refunds add up to the whole surplus of 11
+        assert_eq!(61 - yes_price, 5);
+        assert_eq!(50 - no_price, 6);
+    }
+    
+    #[test]
+    fn mint_rejects_bids_under_set_value() {
+        assert_eq!(mint_prices(60, 39, 100).unwrap_err(), IpredictError::MathOverflow.into());
+        assert_eq!(mint_prices(u64::MAX, 1, 100).unwrap_err(), IpredictError::MathOverflow.into());
+    }
+    
+    #[test]
+    fn burn_splits_even_discount() {
+        // 40 + 50 under a set of 100 leaves 10 to share
+        assert_eq!(burn_prices(40, 50, 100).unwrap(), (45, 55));
+    }
+    
+    #[test]
+    fn burn_at_exactly_set_value() {
+        assert_eq!(burn_prices(30, 70, 100).unwrap(), (30, 70));
+        assert_eq!(burn_prices(0, 100, 100).unwrap(), (0, 100));
+    }
+    
+    #[test]
+    fn burn_gives_odd_lamport_to_no() {
+        let (yes_price, no_price) = burn_prices(40, 49, 100).unwrap();
+        assert_eq!((yes_price, no_price), (45, 55));
+        
+        // Improvements add up to the whole discount of 11
+        assert_eq!(yes_price - 40, 5);
+        assert_eq!(no_price - 49, 6);
+    }
+    
+    #[test]
+    fn burn_rejects_asks_over_set_value() {
+        assert_eq!(burn_prices(40, 61, 100).unwrap_err(), IpredictError::MathOverflow.into());
+        assert_eq!(burn_prices(101, 0, 100).unwrap_err(), IpredictError::MathOverflow.into());
+    }
+    
+    #[test]
+    fn prices_always_sum_to_set_value() {
+        let set_value = 1_000;
+        for yes in (0..=set_value).step_by(37) {
+            for no in (set_value - yes..=set_value).step_by(41) {
+                let (yes_price, no_price) = mint_prices(yes, no, set_value).unwrap();
+                assert_eq!(yes_price + no_price, set_value);
+                assert!(yes_price <= yes && no_price <= no);
+            }
+            for no in (0..=set_value - yes).step_by(41) {
+                let (yes_price, no_price) = burn_prices(yes, no, set_value).unwrap();
+                assert_eq!(yes_price + no_price, set_value);
+                assert!(yes_price >= yes && no_price >= no);
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Program-owned lamport vault holding the SOL locked behind resting buy orders
+/// and the SOL credited to traders' `OpenOrders` but not yet settled
+#[account]
+#[derive(Default)]
+pub struct BidEscrow {
+    pub wager: Pubkey,
+    pub total_locked: u64,
+    pub bump: u8,
+}
+
+impl BidEscrow {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        8 + // total_locked
+        1 + // bump
+        32; // padding
+}
\ No newline at end of file
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use crate::state::Resolution;
+use crate::errors::IpredictError;
+
+/// Governance tokens locked behind each outcome of a DAO-resolved wager
+#[account]
+#[derive(Default)]
+pub struct DaoTally {
+    pub wager: Pubkey,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub draw_votes: u64,
+    pub invalid_votes: u64,
+    pub bump: u8,
+}
+
+impl DaoTally {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        8 + // yes_votes
+        8 + // no_votes
+        8 + // draw_votes
+        8 + // invalid_votes
+        1 + // bump
+        32; // padding
+    
+    /// Add weight behind an outcome and return its new total
+    pub fn add(&mut self, resolution: Resolution, amount: u64) -> Result<u64> {
+        let votes = match resolution {
+            Resolution::YesWon => &mut self.yes_votes,
+            Resolution::NoWon => &mut self.no_votes,
+            Resolution::Draw => &mut self.draw_votes,
+            Resolution::Invalid => &mut self.invalid_votes,
+            Resolution::Pending => return Err(IpredictError::InvalidResolution.into()),
+        };
+        *votes = votes
+            .checked_add(amount)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(*votes)
+    }
+    
+    /// The outcome with the most locked tokens, if at least `quorum` tokens
+    /// were locked in total and no other outcome ties it for the lead
+    pub fn decided(&self, quorum: u64) -> Option<Resolution> {
+        let totals = [
+            (Resolution::YesWon, self.yes_votes),
+            (Resolution::NoWon, self.no_votes),
+            (Resolution::Draw, self.draw_votes),
+            (Resolution::Invalid, self.invalid_votes),
+        ];
+        let total: u128 = totals.iter().map(|&(_, votes)| votes as u128).sum();
+        if total == 0 || total < quorum as u128 {
+            return None;
+        }
+        
+        let (leader, most) = totals
+            .iter()
+            .copied()
+            .max_by_key(|&(_, votes)| votes)?;
+        if totals.iter().filter(|&&(_, votes)| votes == most).count() > 1 {
+            return None;
+        }
+        Some(leader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn tally(yes_votes: u64, no_votes: u64, draw_votes: u64, invalid_votes: u64) -> DaoTally {
+        DaoTally { yes_votes, no_votes, draw_votes, invalid_votes, ..Default::default() }
+    }
+    
+    #[test]
+    fn decides_by_plurality_of_all_locked_tokens() {
+        // No outcome holds a quorum on its own, but the vote as a whole does
+        assert_eq!(tally(40, 30, 20, 10).decided(100), Some(Resolution::YesWon));
+        assert_eq!(tally(10, 20, 45, 25).decided(100), Some(Resolution::Draw));
+        assert_eq!(tally(0, 0, 0, 1).decided(1), Some(Resolution::Invalid));
+    }
+    
+    #[test]
+    fn needs_quorum() {
+        assert_eq!(tally(40, 30, 20, 9).decided(100), None);
+        assert_eq!(tally(0, 0, 0, 0).decided(0), None);
+        assert_eq!(tally(u64::MAX, u64::MAX - 1, 0, 0).decided(u64::MAX), Some(Resolution::YesWon));
+    }
+    
+    #[test]
+    fn ties_for_the_lead_are_undecided() {
+        assert_eq!(tally(50, 50, 0, 0).decided(100), None);
+        assert_eq!(tally(10, 30, 30, 10).decided(10), None);
+        assert_eq!(tally(30, 30, 40, 0).decided(10), Some(Resolution::Draw));
+    }
+}
\ No newline at end of file
@@ -2,11 +2,25 @@ pub mod platform;
 pub mod wager;
 pub mod order_book;
 pub mod user_position;
+pub mod bid_escrow;
+pub mod open_orders;
+pub mod resolution_proposal;
+pub mod resolver_committee;
+pub mod dao_tally;
+pub mod vote_record;
+pub mod resolution_record;
 
 pub use platform::*;
 pub use wager::*;
 pub use order_book::*;
 pub use user_position::*;
+pub use bid_escrow::*;
+pub use open_orders::*;
+pub use resolution_proposal::*;
+pub use resolver_committee::*;
+pub use dao_tally::*;
+pub use vote_record::*;
+pub use resolution_record::*;
 
 use anchor_lang::prelude::*;
 
@@ -15,6 +29,10 @@ pub enum WagerStatus {
     Created,
     Active,
     Resolved,
+    /// A bonded resolution awaits the end of its dispute window
+    Proposed,
+    /// A proposed resolution was challenged and awaits the platform authority
+    Disputed,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
@@ -23,6 +41,8 @@ pub enum Resolution {
     YesWon,
     NoWon,
     Draw,
+    /// The question was void; all collateral is refunded to token holders
+    Invalid,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
@@ -38,6 +58,14 @@ impl TokenType {
             TokenType::No => b"no",
         }
     }
+    
+    /// The other side of a complete set
+    pub fn opposite(&self) -> TokenType {
+        match self {
+            TokenType::Yes => TokenType::No,
+            TokenType::No => TokenType::Yes,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
@@ -46,9 +74,102 @@ pub enum OrderSide {
     Sell,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum OrderType {
+    /// Match what crosses, rest the remainder
+    Limit,
+    /// Rest the whole order; rejected if it would cross
+    PostOnly,
+    /// Match what crosses, cancel the remainder
+    ImmediateOrCancel,
+    /// Match the whole order or nothing
+    FillOrKill,
+}
+
+/// What happens when two orders from the same owner would trade
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SelfTradeBehavior {
+    /// Cancel the remainder of the newer order
+    #[default]
+    CancelNewest,
+    /// Cancel the remainder of the older order
+    CancelOldest,
+    /// Cancel the remainder of both orders
+    CancelBoth,
+    /// Shrink both orders by their overlap, cancelling whichever runs out
+    DecrementAndCancel,
+}
+
+impl SelfTradeBehavior {
+    /// Quantities to cancel from the (newer, older) order instead of trading
+    pub fn cancel_quantities(&self, newer: &Order, older: &Order) -> (u64, u64) {
+        let newer_remaining = newer.remaining_quantity();
+        let older_remaining = older.remaining_quantity();
+        match self {
+            SelfTradeBehavior::CancelNewest => (newer_remaining, 0),
+            SelfTradeBehavior::CancelOldest => (0, older_remaining),
+            SelfTradeBehavior::CancelBoth => (newer_remaining, older_remaining),
+            SelfTradeBehavior::DecrementAndCancel => {
+                let overlap = newer_remaining.min(older_remaining);
+                (overlap, overlap)
+            }
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum ResolutionArbitrator {
     Platform,
     AI,
     DAO,
+    /// Settled from a price feed by `resolve_from_oracle`
+    Oracle,
+    /// Settled by M-of-N votes of a `ResolverCommittee`
+    Committee,
+}
+
+/// How an oracle price is compared against a wager's strike
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum OracleComparator {
+    /// YES if the price is above `strike`
+    GreaterThan,
+    /// YES if the price is below `strike`
+    LessThan,
+    /// YES if the price is within `strike..=upper_strike`
+    Between,
+}
+
+/// The price condition an oracle wager resolves on. Strikes are in the
+/// feed's own units, scaled by `10^exponent`; a feed reporting any other
+/// exponent is refused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct OracleCondition {
+    pub price_account: Pubkey,
+    pub comparator: OracleComparator,
+    pub strike: i64,
+    pub upper_strike: i64,
+    /// Exponent the strikes assume the feed reports prices with
+    pub exponent: i32,
+}
+
+impl OracleCondition {
+    pub const SIZE: usize = 32 + // price_account
+        1 + // comparator
+        8 + // strike
+        8 + // upper_strike
+        4; // exponent
+    
+    /// The outcome a price settles the wager to
+    pub fn resolve(&self, price: i64) -> Resolution {
+        let yes = match self.comparator {
+            OracleComparator::GreaterThan => price > self.strike,
+            OracleComparator::LessThan => price < self.strike,
+            OracleComparator::Between => price >= self.strike && price <= self.upper_strike,
+        };
+        if yes {
+            Resolution::YesWon
+        } else {
+            Resolution::NoWon
+        }
+    }
 }
\ No newline at end of file
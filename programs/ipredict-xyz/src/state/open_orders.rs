@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::state::TokenType;
+use crate::constants::MAX_OPEN_ORDERS;
+use crate::errors::IpredictError;
+
+/// A trader's live orders on one wager, plus their free balances. `sol_free`
+/// is the trader's only SOL balance: deposits, fill proceeds and every refund
+/// of a buy order land there, and buy orders reserve from it. It stays in the
+/// bid escrow until withdrawn or settled; free tokens stay in the escrows.
+#[account]
+#[derive(Default)]
+pub struct OpenOrders {
+    pub owner: Pubkey,
+    pub wager: Pubkey,
+    pub order_ids: Vec<u64>,
+    pub sol_free: u64,
+    pub yes_tokens_free: u64,
+    pub no_tokens_free: u64,
+    pub bump: u8,
+}
+
+impl OpenOrders {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // owner
+        32 + // wager
+        4 + 8 * MAX_OPEN_ORDERS + // order_ids
+        8 + // sol_free
+        8 + // yes_tokens_free
+        8 + // no_tokens_free
+        1 + // bump
+        32; // padding
+    
+    pub fn add_order(&mut self, order_id: u64) -> Result<()> {
+        if self.order_ids.len() >= MAX_OPEN_ORDERS {
+            return Err(IpredictError::TooManyOpenOrders.into());
+        }
+        self.order_ids.push(order_id);
+        Ok(())
+    }
+    
+    pub fn remove_order(&mut self, order_id: u64) {
+        self.order_ids.retain(|id| *id != order_id);
+    }
+    
+    pub fn credit_sol(&mut self, amount: u64) -> Result<()> {
+        self.sol_free = self.sol_free
+            .checked_add(amount)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(())
+    }
+    
+    /// Take free SOL, e.g. to back a buy order or pay out a withdrawal
+    pub fn debit_sol(&mut self, amount: u64) -> Result<()> {
+        self.sol_free = self.sol_free
+            .checked_sub(amount)
+            .ok_or(IpredictError::InsufficientBalance)?;
+        Ok(())
+    }
+    
+    pub fn credit_tokens(&mut self, token_type: TokenType, amount: u64) -> Result<()> {
+        let free = match token_type {
+            TokenType::Yes => &mut self.yes_tokens_free,
+            TokenType::No => &mut self.no_tokens_free,
+        };
+        *free = free
+            .checked_add(amount)
+            .ok_or(IpredictError::MathOverflow)?;
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,88 +1,563 @@
 use anchor_lang::prelude::*;
-use crate::state::{OrderSide, TokenType};
+use crate::state::{OrderSide, SelfTradeBehavior, TokenType};
 use crate::constants::MAX_ORDERS_PER_BOOK;
+use crate::errors::IpredictError;
 
-#[account]
+/// Empty tree / missing node marker
+pub const NIL: u32 = u32::MAX;
+/// Set on a node handle that points into `orders` rather than `inner_nodes`
+const LEAF_FLAG: u32 = 1 << 31;
+
+/// The order book is far larger than `init` can allocate through a CPI, so it
+/// is created by the client ahead of `create_wager` and loaded zero-copy.
+#[account(zero_copy)]
 pub struct OrderBook {
     pub wager: Pubkey,
     pub next_order_id: u64,
-    pub buy_orders_yes: Vec<Order>,
-    pub sell_orders_yes: Vec<Order>,
-    pub buy_orders_no: Vec<Order>,
-    pub sell_orders_no: Vec<Order>,
-    pub bump: u8,
+    pub buy_orders_yes: OrderTree,
+    pub sell_orders_yes: OrderTree,
+    pub buy_orders_no: OrderTree,
+    pub sell_orders_no: OrderTree,
 }
 
 impl OrderBook {
     pub const SIZE: usize = 8 + // discriminator
-        32 + // wager
-        8 + // next_order_id
-        4 + (Order::SIZE * MAX_ORDERS_PER_BOOK) + // buy_orders_yes
-        4 + (Order::SIZE * MAX_ORDERS_PER_BOOK) + // sell_orders_yes
-        4 + (Order::SIZE * MAX_ORDERS_PER_BOOK) + // buy_orders_no
-        4 + (Order::SIZE * MAX_ORDERS_PER_BOOK) + // sell_orders_no
-        1 + // bump
-        256; // padding
+        std::mem::size_of::<OrderBook>();
     
-    pub fn add_order(&mut self, order: Order) -> Result<()> {
-        let orders = match (order.side, order.token_type) {
+    pub fn init(&mut self, wager: Pubkey) {
+        self.wager = wager;
+        self.next_order_id = 0;
+        self.buy_orders_yes.init(OrderSide::Buy, TokenType::Yes);
+        self.sell_orders_yes.init(OrderSide::Sell, TokenType::Yes);
+        self.buy_orders_no.init(OrderSide::Buy, TokenType::No);
+        self.sell_orders_no.init(OrderSide::Sell, TokenType::No);
+    }
+    
+    pub fn tree(&self, side: OrderSide, token_type: TokenType) -> &OrderTree {
+        match (side, token_type) {
+            (OrderSide::Buy, TokenType::Yes) => &self.buy_orders_yes,
+            (OrderSide::Sell, TokenType::Yes) => &self.sell_orders_yes,
+            (OrderSide::Buy, TokenType::No) => &self.buy_orders_no,
+            (OrderSide::Sell, TokenType::No) => &self.sell_orders_no,
+        }
+    }
+    
+    pub fn tree_mut(&mut self, side: OrderSide, token_type: TokenType) -> &mut OrderTree {
+        match (side, token_type) {
             (OrderSide::Buy, TokenType::Yes) => &mut self.buy_orders_yes,
             (OrderSide::Sell, TokenType::Yes) => &mut self.sell_orders_yes,
             (OrderSide::Buy, TokenType::No) => &mut self.buy_orders_no,
             (OrderSide::Sell, TokenType::No) => &mut self.sell_orders_no,
+        }
+    }
+    
+    /// Both sides of one outcome's book, as (bids, asks)
+    pub fn sides_mut(&mut self, token_type: TokenType) -> (&mut OrderTree, &mut OrderTree) {
+        match token_type {
+            TokenType::Yes => (&mut self.buy_orders_yes, &mut self.sell_orders_yes),
+            TokenType::No => (&mut self.buy_orders_no, &mut self.sell_orders_no),
+        }
+    }
+    
+    pub fn add_order(&mut self, order: Order) -> Result<()> {
+        self.tree_mut(order.side(), order.token_type()).insert(order)
+    }
+    
+    pub fn remove_order(&mut self, order_id: u64, side: OrderSide, token_type: TokenType) -> Result<Order> {
+        let tree = self.tree_mut(side, token_type);
+        let index = tree.find_by_id(order_id).ok_or(IpredictError::OrderNotFound)?;
+        Ok(tree.remove(index))
+    }
+    
+    /// Best unexpired resting order on one side of the book
+    pub fn best_order(&self, side: OrderSide, token_type: TokenType, now: i64) -> Option<(u32, Order)> {
+        let tree = self.tree(side, token_type);
+        tree.best_live(now).map(|index| (index, *tree.order(index)))
+    }
+    
+    /// The most profitable crossing available in the book, if any. A YES bid
+    /// and a NO bid that together cover `set_value` can be filled by minting a
+    /// complete set, and a YES ask and a NO ask that together ask no more than
+    /// `set_value` can be filled by burning one, so both outcomes' books are
+    /// matched as a single market.
+    pub fn next_cross(&self, set_value: u64, now: i64) -> Option<Cross> {
+        let price = |side, token_type| self.best_order(side, token_type, now).map(|(_, order)| order.price);
+        let yes_bid = price(OrderSide::Buy, TokenType::Yes);
+        let yes_ask = price(OrderSide::Sell, TokenType::Yes);
+        let no_bid = price(OrderSide::Buy, TokenType::No);
+        let no_ask = price(OrderSide::Sell, TokenType::No);
+        
+        // Per-token surplus each crossing would release
+        let candidates = [
+            (Cross::Direct(TokenType::Yes), yes_bid.zip(yes_ask).and_then(|(bid, ask)| bid.checked_sub(ask))),
+            (Cross::Direct(TokenType::No), no_bid.zip(no_ask).and_then(|(bid, ask)| bid.checked_sub(ask))),
+            (Cross::Mint, yes_bid.zip(no_bid).and_then(|(yes, no)| yes.saturating_add(no).checked_sub(set_value))),
+            (Cross::Burn, yes_ask.zip(no_ask).and_then(|(yes, no)| set_value.checked_sub(yes.saturating_add(no)))),
+        ];
+        
+        // Largest surplus, the first listed on ties
+        candidates
+            .into_iter()
+            .filter_map(|(cross, surplus)| Some((cross, surplus?)))
+            .min_by_key(|&(_, surplus)| std::cmp::Reverse(surplus))
+            .map(|(cross, _)| cross)
+    }
+    
+    /// Resting book an incoming order would trade against next: the opposite
+    /// side of its own outcome, or the same side of the complement outcome,
+    /// whichever gives the better price
+    pub fn best_match(&self, order: &Order, set_value: u64, now: i64) -> Option<(OrderSide, TokenType)> {
+        let side = order.side();
+        let token_type = order.token_type();
+        let opposite_side = match side {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        };
+        let other_token_type = match token_type {
+            TokenType::Yes => TokenType::No,
+            TokenType::No => TokenType::Yes,
         };
         
-        if orders.len() >= MAX_ORDERS_PER_BOOK {
-            return Err(crate::errors::IpredictError::OrderBookFull.into());
+        let direct = self
+            .best_order(opposite_side, token_type, now)
+            .and_then(|(_, resting)| crossing_surplus(order, &resting, set_value))
+            .map(|surplus| ((opposite_side, token_type), surplus));
+        let complement = self
+            .best_order(side, other_token_type, now)
+            .and_then(|(_, resting)| crossing_surplus(order, &resting, set_value))
+            .map(|surplus| ((side, other_token_type), surplus));
+        
+        match (direct, complement) {
+            (Some(direct), Some(complement)) if complement.1 > direct.1 => Some(complement.0),
+            (Some(direct), _) => Some(direct.0),
+            (None, complement) => complement.map(|(book, _)| book),
         }
+    }
+    
+    /// Quantity an incoming order could fill immediately across both books,
+    /// ignoring its owner's own orders
+    pub fn fillable_quantity(&self, order: &Order, set_value: u64, now: i64) -> u64 {
+        let mut fillable = 0u64;
+        for (side, token_type) in [
+            (OrderSide::Buy, TokenType::Yes),
+            (OrderSide::Sell, TokenType::Yes),
+            (OrderSide::Buy, TokenType::No),
+            (OrderSide::Sell, TokenType::No),
+        ] {
+            let tree = self.tree(side, token_type);
+            for index in tree.iter() {
+                let resting = tree.order(index);
+                if resting.is_expired(now) || resting.owner == order.owner {
+                    continue;
+                }
+                if crossing_surplus(order, resting, set_value).is_none() {
+                    break;
+                }
+                fillable = fillable.saturating_add(resting.remaining_quantity());
+                if fillable >= order.remaining_quantity() {
+                    return fillable;
+                }
+            }
+        }
+        fillable
+    }
+    
+    /// Locate an order by id across all four sides
+    pub fn find_order(&self, order_id: u64) -> Option<Order> {
+        [
+            &self.buy_orders_yes,
+            &self.sell_orders_yes,
+            &self.buy_orders_no,
+            &self.sell_orders_no,
+        ]
+        .into_iter()
+        .find_map(|tree| tree.find_by_id(order_id).map(|index| *tree.order(index)))
+    }
+}
+
+/// Per-token surplus released by trading `order` against `resting`, or `None`
+/// when the two do not cross. Orders on the same side of the same outcome
+/// never cross.
+pub fn crossing_surplus(order: &Order, resting: &Order, set_value: u64) -> Option<u64> {
+    let (bid, ask) = match (order.side(), resting.side()) {
+        (OrderSide::Buy, OrderSide::Sell) => (order.price, resting.price),
+        (OrderSide::Sell, OrderSide::Buy) => (resting.price, order.price),
+        _ if order.token_type() == resting.token_type() => return None,
+        (OrderSide::Buy, OrderSide::Buy) => {
+            return order.price.saturating_add(resting.price).checked_sub(set_value);
+        }
+        (OrderSide::Sell, OrderSide::Sell) => {
+            return set_value.checked_sub(order.price.saturating_add(resting.price));
+        }
+    };
+    if order.token_type() == resting.token_type() {
+        bid.checked_sub(ask)
+    } else {
+        None
+    }
+}
+
+/// Kind of trade that clears the top of the book
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cross {
+    /// A bid and an ask for the same outcome
+    Direct(TokenType),
+    /// A YES bid and a NO bid, filled by minting complete sets
+    Mint,
+    /// A YES ask and a NO ask, filled by burning complete sets
+    Burn,
+}
+
+#[zero_copy]
+pub struct InnerNode {
+    /// Bit of the 128-bit order key this node branches on
+    pub crit_bit: u32,
+    pub children: [u32; 2],
+}
+
+/// A crit-bit tree of orders stored in fixed slabs. Orders are keyed on
+/// (price, order id), so walking the tree visits them in price-time priority
+/// and inserts and removals only touch one root-to-leaf path. Both slabs are
+/// kept dense by moving the last node into any freed slot.
+#[zero_copy]
+pub struct OrderTree {
+    pub root: u32,
+    pub leaf_count: u32,
+    pub inner_count: u32,
+    pub side: u8,
+    pub token_type: u8,
+    pub padding: [u8; 2],
+    pub inner_nodes: [InnerNode; MAX_ORDERS_PER_BOOK],
+    pub orders: [Order; MAX_ORDERS_PER_BOOK],
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Root,
+    Child(u32, usize),
+}
+
+impl OrderTree {
+    pub fn init(&mut self, side: OrderSide, token_type: TokenType) {
+        self.root = NIL;
+        self.leaf_count = 0;
+        self.inner_count = 0;
+        self.side = side as u8;
+        self.token_type = token_type as u8;
+    }
+    
+    pub fn len(&self) -> usize {
+        self.leaf_count as usize
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.leaf_count == 0
+    }
+    
+    pub fn order(&self, index: u32) -> &Order {
+        &self.orders[index as usize]
+    }
+    
+    pub fn order_mut(&mut self, index: u32) -> &mut Order {
+        &mut self.orders[index as usize]
+    }
+    
+    /// Live orders in slab order (not price order)
+    pub fn orders(&self) -> &[Order] {
+        &self.orders[..self.len()]
+    }
+    
+    /// Bids rank highest price first, asks lowest price first, and both
+    /// rank earlier orders first at the same price.
+    fn key(&self, order: &Order) -> u128 {
+        let sequence = if self.side == OrderSide::Buy as u8 { !order.id } else { order.id };
+        ((order.price as u128) << 64) | sequence as u128
+    }
+    
+    /// Child to follow towards the best order
+    fn best_direction(&self) -> usize {
+        if self.side == OrderSide::Buy as u8 { 1 } else { 0 }
+    }
+    
+    fn slot(&self, slot: Slot) -> u32 {
+        match slot {
+            Slot::Root => self.root,
+            Slot::Child(inner, direction) => self.inner_nodes[inner as usize].children[direction],
+        }
+    }
+    
+    fn set_slot(&mut self, slot: Slot, handle: u32) {
+        match slot {
+            Slot::Root => self.root = handle,
+            Slot::Child(inner, direction) => self.inner_nodes[inner as usize].children[direction] = handle,
+        }
+    }
+    
+    /// Walk from the root along `key` until reaching `target`
+    fn find_slot(&self, key: u128, target: u32) -> Slot {
+        let mut slot = Slot::Root;
+        loop {
+            let handle = self.slot(slot);
+            if handle == target || handle & LEAF_FLAG != 0 {
+                return slot;
+            }
+            let inner = &self.inner_nodes[handle as usize];
+            slot = Slot::Child(handle, ((key >> inner.crit_bit) & 1) as usize);
+        }
+    }
+    
+    /// Leaf reached by following `key` from the root; the closest match when
+    /// `key` is not in the tree
+    fn closest_leaf(&self, key: u128) -> u32 {
+        let mut handle = self.root;
+        while handle & LEAF_FLAG == 0 {
+            let inner = &self.inner_nodes[handle as usize];
+            handle = inner.children[((key >> inner.crit_bit) & 1) as usize];
+        }
+        handle & !LEAF_FLAG
+    }
+    
+    pub fn insert(&mut self, order: Order) -> Result<()> {
+        if self.len() >= MAX_ORDERS_PER_BOOK {
+            return Err(IpredictError::OrderBookFull.into());
+        }
+        
+        let index = self.leaf_count;
+        self.orders[index as usize] = order;
+        self.leaf_count += 1;
+        let handle = index | LEAF_FLAG;
         
-        orders.push(order);
+        if self.root == NIL {
+            self.root = handle;
+            return Ok(());
+        }
+        
+        let key = self.key(&order);
+        let closest_key = self.key(self.order(self.closest_leaf(key)));
+        if closest_key == key {
+            self.leaf_count -= 1;
+            return Err(IpredictError::DuplicateOrder.into());
+        }
+        let crit_bit = 127 - (key ^ closest_key).leading_zeros();
         
-        // Sort by price (descending for buy, ascending for sell)
-        match order.side {
-            OrderSide::Buy => orders.sort_by(|a, b| b.price.cmp(&a.price)),
-            OrderSide::Sell => orders.sort_by(|a, b| a.price.cmp(&b.price)),
+        // Descend until the next branch tests a less significant bit
+        let mut slot = Slot::Root;
+        loop {
+            let current = self.slot(slot);
+            if current & LEAF_FLAG != 0 {
+                break;
+            }
+            let inner = &self.inner_nodes[current as usize];
+            if inner.crit_bit < crit_bit {
+                break;
+            }
+            slot = Slot::Child(current, ((key >> inner.crit_bit) & 1) as usize);
         }
         
+        let direction = ((key >> crit_bit) & 1) as usize;
+        let mut children = [NIL; 2];
+        children[direction] = handle;
+        children[1 - direction] = self.slot(slot);
+        
+        let inner = self.inner_count;
+        self.inner_nodes[inner as usize] = InnerNode { crit_bit, children };
+        self.inner_count += 1;
+        self.set_slot(slot, inner);
+        
         Ok(())
     }
     
-    pub fn remove_order(&mut self, order_id: u64, side: OrderSide, token_type: TokenType) -> Result<Order> {
-        let orders = match (side, token_type) {
-            (OrderSide::Buy, TokenType::Yes) => &mut self.buy_orders_yes,
-            (OrderSide::Sell, TokenType::Yes) => &mut self.sell_orders_yes,
-            (OrderSide::Buy, TokenType::No) => &mut self.buy_orders_no,
-            (OrderSide::Sell, TokenType::No) => &mut self.sell_orders_no,
-        };
+    /// Unlink the order at `index` and return it
+    pub fn remove(&mut self, index: u32) -> Order {
+        let order = *self.order(index);
+        let key = self.key(&order);
         
-        let position = orders.iter().position(|o| o.id == order_id)
-            .ok_or(crate::errors::IpredictError::OrderNotFound)?;
+        let leaf_slot = self.find_slot(key, index | LEAF_FLAG);
+        match leaf_slot {
+            Slot::Root => self.root = NIL,
+            Slot::Child(parent, direction) => {
+                // Replace the parent with the removed leaf's sibling
+                let sibling = self.inner_nodes[parent as usize].children[1 - direction];
+                let parent_slot = self.find_slot(key, parent);
+                self.set_slot(parent_slot, sibling);
+                self.free_inner(parent);
+            }
+        }
+        self.free_leaf(index);
         
-        Ok(orders.remove(position))
+        order
+    }
+    
+    /// Record a fill against the order at `index`, removing it once complete
+    pub fn fill(&mut self, index: u32, quantity: u64) -> Result<bool> {
+        let order = self.order_mut(index);
+        order.filled_quantity = order.filled_quantity
+            .checked_add(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        let filled = order.is_filled();
+        if filled {
+            self.remove(index);
+        }
+        Ok(filled)
+    }
+    
+    /// Cancel part of the order at `index` without filling it, removing it
+    /// once nothing is left
+    pub fn reduce(&mut self, index: u32, quantity: u64) -> Result<bool> {
+        let order = self.order_mut(index);
+        order.quantity = order.quantity
+            .checked_sub(quantity)
+            .ok_or(IpredictError::MathOverflow)?;
+        let emptied = order.is_filled();
+        if emptied {
+            self.remove(index);
+        }
+        Ok(emptied)
     }
+    
+    fn free_inner(&mut self, inner: u32) {
+        let last = self.inner_count - 1;
+        if inner != last {
+            // Route to the moved node through any leaf beneath it
+            let mut handle = last;
+            while handle & LEAF_FLAG == 0 {
+                handle = self.inner_nodes[handle as usize].children[0];
+            }
+            let key = self.key(self.order(handle & !LEAF_FLAG));
+            let slot = self.find_slot(key, last);
+            self.inner_nodes[inner as usize] = self.inner_nodes[last as usize];
+            self.set_slot(slot, inner);
+        }
+        self.inner_count = last;
+    }
+    
+    fn free_leaf(&mut self, index: u32) {
+        let last = self.leaf_count - 1;
+        if index != last {
+            let key = self.key(self.order(last));
+            let slot = self.find_slot(key, last | LEAF_FLAG);
+            self.orders[index as usize] = self.orders[last as usize];
+            self.set_slot(slot, index | LEAF_FLAG);
+        }
+        self.leaf_count = last;
+    }
+    
+    /// Slab index of the best-priced order
+    pub fn best(&self) -> Option<u32> {
+        if self.root == NIL {
+            return None;
+        }
+        let direction = self.best_direction();
+        let mut handle = self.root;
+        while handle & LEAF_FLAG == 0 {
+            handle = self.inner_nodes[handle as usize].children[direction];
+        }
+        Some(handle & !LEAF_FLAG)
+    }
+    
+    /// Slab index of the best-priced order that has not expired
+    pub fn best_live(&self, now: i64) -> Option<u32> {
+        self.iter().find(|&index| !self.order(index).is_expired(now))
+    }
+    
+    pub fn find_by_id(&self, order_id: u64) -> Option<u32> {
+        self.orders()
+            .iter()
+            .position(|order| order.id == order_id)
+            .map(|index| index as u32)
+    }
+    
+    /// Slab indices of orders in price-time priority
+    pub fn iter(&self) -> OrderTreeIter<'_> {
+        let mut stack = Vec::new();
+        if self.root != NIL {
+            stack.push(self.root);
+        }
+        OrderTreeIter { tree: self, stack }
+    }
+}
+
+pub struct OrderTreeIter<'a> {
+    tree: &'a OrderTree,
+    stack: Vec<u32>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+impl<'a> Iterator for OrderTreeIter<'a> {
+    type Item = u32;
+    
+    fn next(&mut self) -> Option<u32> {
+        let direction = self.tree.best_direction();
+        while let Some(handle) = self.stack.pop() {
+            if handle & LEAF_FLAG != 0 {
+                return Some(handle & !LEAF_FLAG);
+            }
+            let inner = &self.tree.inner_nodes[handle as usize];
+            self.stack.push(inner.children[1 - direction]);
+            self.stack.push(inner.children[direction]);
+        }
+        None
+    }
+}
+
+#[zero_copy]
+#[derive(Debug)]
 pub struct Order {
     pub id: u64,
     pub owner: Pubkey,
-    pub side: OrderSide,
-    pub token_type: TokenType,
     pub price: u64, // Price per token in lamports
     pub quantity: u64,
     pub filled_quantity: u64,
     pub timestamp: i64,
+    pub expires_at: i64, // Unix timestamp, 0 = good til cancelled
+    pub side: u8,
+    pub token_type: u8,
+    pub self_trade_behavior: u8,
+    pub padding: [u8; 5],
 }
 
 impl Order {
-    pub const SIZE: usize = 8 + // id
-        32 + // owner
-        1 + // side
-        1 + // token_type
-        8 + // price
-        8 + // quantity
-        8 + // filled_quantity
-        8; // timestamp
+    pub const SIZE: usize = std::mem::size_of::<Order>();
+    
+    pub fn new(
+        id: u64,
+        owner: Pubkey,
+        side: OrderSide,
+        token_type: TokenType,
+        price: u64,
+        quantity: u64,
+        timestamp: i64,
+    ) -> Self {
+        Self {
+            id,
+            owner,
+            price,
+            quantity,
+            filled_quantity: 0,
+            timestamp,
+            expires_at: 0,
+            side: side as u8,
+            token_type: token_type as u8,
+            self_trade_behavior: SelfTradeBehavior::default() as u8,
+            padding: [0; 5],
+        }
+    }
+    
+    pub fn side(&self) -> OrderSide {
+        if self.side == OrderSide::Buy as u8 { OrderSide::Buy } else { OrderSide::Sell }
+    }
+    
+    pub fn token_type(&self) -> TokenType {
+        if self.token_type == TokenType::Yes as u8 { TokenType::Yes } else { TokenType::No }
+    }
+    
+    pub fn self_trade_behavior(&self) -> SelfTradeBehavior {
+        match self.self_trade_behavior {
+            1 => SelfTradeBehavior::CancelOldest,
+            2 => SelfTradeBehavior::CancelBoth,
+            3 => SelfTradeBehavior::DecrementAndCancel,
+            _ => SelfTradeBehavior::CancelNewest,
+        }
+    }
     
     pub fn remaining_quantity(&self) -> u64 {
         self.quantity.saturating_sub(self.filled_quantity)
@@ -91,4 +566,344 @@ impl Order {
     pub fn is_filled(&self) -> bool {
         self.filled_quantity >= self.quantity
     }
+    
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    
+    fn new_tree(side: OrderSide) -> Box<OrderTree> {
+        let mut tree = Box::new(OrderTree::zeroed());
+        tree.init(side, TokenType::Yes);
+        tree
+    }
+    
+    fn order(id: u64, side: OrderSide, price: u64) -> Order {
+        Order::new(id, Pubkey::default(), side, TokenType::Yes, price, 10, 0)
+    }
+    
+    /// xorshift64, so the tests need no extra dependencies
+    struct Rng(u64);
+    
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+    
+    /// Keys of every leaf under `handle`, checking each inner node branches
+    /// on a less significant bit than its parent and sorts its leaves by it
+    fn walk(tree: &OrderTree, handle: u32, parent_bit: u32, leaves: &mut [bool], inners: &mut [bool]) -> Vec<u128> {
+        if handle & LEAF_FLAG != 0 {
+            let index = (handle & !LEAF_FLAG) as usize;
+            assert!(index < tree.len(), "leaf outside the slab");
+            assert!(!leaves[index], "leaf reached twice");
+            leaves[index] = true;
+            return vec![tree.key(tree.order(index as u32))];
+        }
+        
+        assert!(handle < tree.inner_count, "inner node outside the slab");
+        assert!(!inners[handle as usize], "inner node reached twice");
+        inners[handle as usize] = true;
+        let inner = tree.inner_nodes[handle as usize];
+        assert!(inner.crit_bit < parent_bit, "crit bits must decrease downwards");
+        
+        let mut keys = Vec::new();
+        for direction in 0..2 {
+            for key in walk(tree, inner.children[direction], inner.crit_bit, leaves, inners) {
+                assert_eq!(((key >> inner.crit_bit) & 1) as usize, direction);
+                keys.push(key);
+            }
+        }
+        let prefix = |key: u128| key.checked_shr(inner.crit_bit + 1).unwrap_or(0);
+        assert!(keys.iter().all(|&key| prefix(key) == prefix(keys[0])), "leaves must share the prefix above the crit bit");
+        keys
+    }
+    
+    fn check_invariants(tree: &OrderTree) {
+        let len = tree.len();
+        assert_eq!(tree.inner_count as usize, len.saturating_sub(1));
+        if len == 0 {
+            assert_eq!(tree.root, NIL);
+            assert_eq!(tree.best(), None);
+            assert_eq!(tree.iter().count(), 0);
+            return;
+        }
+        
+        let mut leaves = vec![false; len];
+        let mut inners = vec![false; len - 1];
+        walk(tree, tree.root, 128, &mut leaves, &mut inners);
+        assert!(leaves.iter().all(|&seen| seen), "unreachable leaf");
+        assert!(inners.iter().all(|&seen| seen), "unreachable inner node");
+        
+        // Iteration visits every order once, best first
+        let keys: Vec<u128> = tree.iter().map(|index| tree.key(tree.order(index))).collect();
+        assert_eq!(keys.len(), len);
+        assert_eq!(tree.best(), tree.iter().next());
+        for pair in keys.windows(2) {
+            if tree.side == OrderSide::Buy as u8 {
+                assert!(pair[0] > pair[1]);
+            } else {
+                assert!(pair[0] < pair[1]);
+            }
+        }
+    }
+    
+    fn ids(tree: &OrderTree) -> Vec<u64> {
+        tree.iter().map(|index| tree.order(index).id).collect()
+    }
+    
+    #[test]
+    fn insert_and_remove_in_random_order() {
+        for side in [OrderSide::Buy, OrderSide::Sell] {
+            let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+            let mut tree = new_tree(side);
+            let mut live = Vec::new();
+            
+            for id in 0..300 {
+                // Few distinct prices, so many orders tie on price
+                tree.insert(order(id, side, 1 + rng.next() % 20)).unwrap();
+                live.push(id);
+                check_invariants(&tree);
+            }
+            
+            while !live.is_empty() {
+                let id = live.swap_remove((rng.next() % live.len() as u64) as usize);
+                let index = tree.find_by_id(id).unwrap();
+                assert_eq!(tree.remove(index).id, id);
+                assert_eq!(tree.find_by_id(id), None);
+                check_invariants(&tree);
+            }
+            assert!(tree.is_empty());
+        }
+    }
+    
+    #[test]
+    fn bids_rank_highest_price_then_earliest() {
+        let mut tree = new_tree(OrderSide::Buy);
+        for (id, price) in [(0, 50), (1, 70), (2, 50), (3, 60), (4, 70)] {
+            tree.insert(order(id, OrderSide::Buy, price)).unwrap();
+        }
+        assert_eq!(ids(&tree), vec![1, 4, 3, 0, 2]);
+    }
+    
+    #[test]
+    fn asks_rank_lowest_price_then_earliest() {
+        let mut tree = new_tree(OrderSide::Sell);
+        for (id, price) in [(0, 50), (1, 70), (2, 50), (3, 60), (4, 70)] {
+            tree.insert(order(id, OrderSide::Sell, price)).unwrap();
+        }
+        assert_eq!(ids(&tree), vec![0, 2, 3, 1, 4]);
+    }
+    
+    #[test]
+    fn best_live_skips_expired_orders() {
+        let mut tree = new_tree(OrderSide::Sell);
+        let mut expired = order(0, OrderSide::Sell, 40);
+        expired.expires_at = 100;
+        tree.insert(expired).unwrap();
+        tree.insert(order(1, OrderSide::Sell, 45)).unwrap();
+        
+        assert_eq!(tree.best_live(99).map(|index| tree.order(index).id), Some(0));
+        assert_eq!(tree.best_live(100).map(|index| tree.order(index).id), Some(1));
+    }
+    
+    #[test]
+    fn freed_slots_are_reused() {
+        let mut tree = new_tree(OrderSide::Buy);
+        
+        // Far more inserts than the slabs hold, never more than a few live
+        for id in 0..3 * MAX_ORDERS_PER_BOOK as u64 {
+            tree.insert(order(id, OrderSide::Buy, 1 + id % 7)).unwrap();
+            if tree.len() > 4 {
+                let index = tree.best().unwrap();
+                tree.remove(index);
+            }
+            assert!(tree.len() <= 4);
+            check_invariants(&tree);
+        }
+        
+        // A removal from the middle moves the last order into its slot
+        let moved = tree.order(tree.len() as u32 - 1).id;
+        tree.remove(0);
+        assert_eq!(tree.find_by_id(moved), Some(0));
+        check_invariants(&tree);
+    }
+    
+    #[test]
+    fn remove_root_leaf() {
+        let mut tree = new_tree(OrderSide::Sell);
+        tree.insert(order(7, OrderSide::Sell, 10)).unwrap();
+        assert_eq!(tree.remove(0).id, 7);
+        check_invariants(&tree);
+        
+        // The emptied tree takes orders again
+        tree.insert(order(8, OrderSide::Sell, 10)).unwrap();
+        assert_eq!(ids(&tree), vec![8]);
+        check_invariants(&tree);
+    }
+    
+    #[test]
+    fn remove_root_inner_node() {
+        let mut tree = new_tree(OrderSide::Sell);
+        tree.insert(order(0, OrderSide::Sell, 10)).unwrap();
+        tree.insert(order(1, OrderSide::Sell, 20)).unwrap();
+        assert_eq!(tree.inner_count, 1);
+        
+        tree.remove(tree.find_by_id(0).unwrap());
+        assert_eq!(tree.root, LEAF_FLAG);
+        assert_eq!(ids(&tree), vec![1]);
+        check_invariants(&tree);
+    }
+    
+    #[test]
+    fn remove_last_leaf() {
+        let mut tree = new_tree(OrderSide::Buy);
+        for id in 0..5 {
+            tree.insert(order(id, OrderSide::Buy, 10 + id)).unwrap();
+        }
+        let last = tree.len() as u32 - 1;
+        assert_eq!(tree.remove(last).id, 4);
+        assert_eq!(ids(&tree), vec![3, 2, 1, 0]);
+        check_invariants(&tree);
+    }
+    
+    #[test]
+    fn rejects_duplicates() {
+        let mut tree = new_tree(OrderSide::Buy);
+        tree.insert(order(1, OrderSide::Buy, 10)).unwrap();
+        tree.insert(order(2, OrderSide::Buy, 10)).unwrap();
+        assert_eq!(
+            tree.insert(order(1, OrderSide::Buy, 10)).unwrap_err(),
+            IpredictError::DuplicateOrder.into()
+        );
+        assert_eq!(ids(&tree), vec![1, 2]);
+        check_invariants(&tree);
+    }
+    
+    #[test]
+    fn fills_to_capacity() {
+        let mut tree = new_tree(OrderSide::Sell);
+        for id in 0..MAX_ORDERS_PER_BOOK as u64 {
+            tree.insert(order(id, OrderSide::Sell, 1 + id % 13)).unwrap();
+        }
+        check_invariants(&tree);
+        
+        let overflow = order(MAX_ORDERS_PER_BOOK as u64, OrderSide::Sell, 1);
+        assert_eq!(tree.insert(overflow).unwrap_err(), IpredictError::OrderBookFull.into());
+        assert_eq!(tree.len(), MAX_ORDERS_PER_BOOK);
+        check_invariants(&tree);
+        
+        // Removing any order makes room again
+        tree.remove(17);
+        tree.insert(overflow).unwrap();
+        assert_eq!(tree.len(), MAX_ORDERS_PER_BOOK);
+        check_invariants(&tree);
+    }
+    
+    fn new_book() -> Box<OrderBook> {
+        let mut book = Box::new(OrderBook::zeroed());
+        book.init(Pubkey::default());
+        book
+    }
+    
+    fn add(book: &mut OrderBook, id: u64, side: OrderSide, token_type: TokenType, price: u64) -> Order {
+        let order = Order::new(id, Pubkey::new_unique(), side, token_type, price, 10, 0);
+        book.add_order(order).unwrap();
+        order
+    }
+    
+    #[test]
+    fn crossing_surplus_by_pair() {
+        let owner = Pubkey::default();
+        let yes_bid = Order::new(0, owner, OrderSide::Buy, TokenType::Yes, 60, 1, 0);
+        let yes_ask = Order::new(1, owner, OrderSide::Sell, TokenType::Yes, 55, 1, 0);
+        let no_bid = Order::new(2, owner, OrderSide::Buy, TokenType::No, 45, 1, 0);
+        let no_ask = Order::new(3, owner, OrderSide::Sell, TokenType::No, 40, 1, 0);
+        
+        assert_eq!(crossing_surplus(&yes_bid, &yes_ask, 100), Some(5));
+        assert_eq!(crossing_surplus(&yes_ask, &yes_bid, 100), Some(5));
+        assert_eq!(crossing_surplus(&yes_bid, &no_bid, 100), Some(5));
+        assert_eq!(crossing_surplus(&yes_ask, &no_ask, 100), Some(5));
+        
+        // Same side of the same outcome, and opposite sides of different outcomes
+        assert_eq!(crossing_surplus(&yes_bid, &yes_bid, 100), None);
+        assert_eq!(crossing_surplus(&yes_bid, &no_ask, 100), None);
+        assert_eq!(crossing_surplus(&no_bid, &yes_ask, 100), None);
+    }
+    
+    #[test]
+    fn bids_at_exactly_set_value_cross_by_minting() {
+        let mut book = new_book();
+        add(&mut book, 0, OrderSide::Buy, TokenType::Yes, 60);
+        add(&mut book, 1, OrderSide::Buy, TokenType::No, 39);
+        assert_eq!(book.next_cross(100, 0), None);
+        
+        add(&mut book, 2, OrderSide::Buy, TokenType::No, 40);
+        assert_eq!(book.next_cross(100, 0), Some(Cross::Mint));
+    }
+    
+    #[test]
+    fn asks_at_exactly_set_value_cross_by_burning() {
+        let mut book = new_book();
+        add(&mut book, 0, OrderSide::Sell, TokenType::Yes, 30);
+        add(&mut book, 1, OrderSide::Sell, TokenType::No, 71);
+        assert_eq!(book.next_cross(100, 0), None);
+        
+        add(&mut book, 2, OrderSide::Sell, TokenType::No, 70);
+        assert_eq!(book.next_cross(100, 0), Some(Cross::Burn));
+    }
+    
+    #[test]
+    fn next_cross_takes_largest_surplus() {
+        let mut book = new_book();
+        add(&mut book, 0, OrderSide::Buy, TokenType::Yes, 65);
+        add(&mut book, 1, OrderSide::Sell, TokenType::Yes, 62);
+        assert_eq!(book.next_cross(100, 0), Some(Cross::Direct(TokenType::Yes)));
+        
+        // A NO bid releasing 7 beats the direct YES surplus of 3
+        add(&mut book, 2, OrderSide::Buy, TokenType::No, 42);
+        assert_eq!(book.next_cross(100, 0), Some(Cross::Mint));
+        
+        // Expired orders never cross
+        let mut expired = Order::new(3, Pubkey::new_unique(), OrderSide::Sell, TokenType::No, 1, 10, 0);
+        expired.expires_at = 50;
+        book.add_order(expired).unwrap();
+        assert_eq!(book.next_cross(100, 49), Some(Cross::Direct(TokenType::No)));
+        assert_eq!(book.next_cross(100, 50), Some(Cross::Mint));
+    }
+    
+    #[test]
+    fn best_match_prefers_better_complement() {
+        let mut book = new_book();
+        add(&mut book, 0, OrderSide::Sell, TokenType::Yes, 58);
+        let taker = Order::new(9, Pubkey::new_unique(), OrderSide::Buy, TokenType::Yes, 60, 30, 0);
+        assert_eq!(book.best_match(&taker, 100, 0), Some((OrderSide::Sell, TokenType::Yes)));
+        
+        // A NO bid at 45 fills the YES bid at 55 through a mint
+        add(&mut book, 1, OrderSide::Buy, TokenType::No, 45);
+        assert_eq!(book.best_match(&taker, 100, 0), Some((OrderSide::Buy, TokenType::No)));
+        assert_eq!(book.fillable_quantity(&taker, 100, 0), 20);
+    }
+    
+    #[test]
+    fn fill_and_reduce_remove_finished_orders() {
+        let mut tree = new_tree(OrderSide::Buy);
+        tree.insert(order(0, OrderSide::Buy, 10)).unwrap();
+        tree.insert(order(1, OrderSide::Buy, 20)).unwrap();
+        
+        assert!(!tree.fill(tree.find_by_id(1).unwrap(), 4).unwrap());
+        assert!(tree.fill(tree.find_by_id(1).unwrap(), 6).unwrap());
+        assert!(!tree.reduce(tree.find_by_id(0).unwrap(), 3).unwrap());
+        assert!(tree.reduce(tree.find_by_id(0).unwrap(), 7).unwrap());
+        check_invariants(&tree);
+    }
 }
\ No newline at end of file
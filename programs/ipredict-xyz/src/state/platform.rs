@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::state::SelfTradeBehavior;
+use crate::constants::*;
 
 #[account]
 #[derive(Default)]
@@ -11,6 +13,21 @@ pub struct Platform {
     pub platform_fee_bps: u16,
     pub deployer_fee_bps: u16,
     pub wager_creation_fee: u64,
+    pub self_trade_behavior: SelfTradeBehavior,
+    /// Lamports staked to propose a resolution, and again to dispute one
+    pub resolution_bond: u64,
+    /// Seconds a proposed resolution stays open to dispute
+    pub dispute_window: i64,
+    /// Lamports each wager's creator puts up for whoever resolves it correctly
+    pub proposer_reward: u64,
+    /// Seconds after `resolution_time` before anyone can void an unresolved wager
+    pub resolution_grace_period: i64,
+    /// SPL mint DAO voters lock; `Pubkey::default()` until DAO resolution is configured
+    pub governance_mint: Pubkey,
+    /// Seconds after `resolution_time` that DAO votes are accepted
+    pub dao_voting_period: i64,
+    /// Locked governance tokens an outcome needs to resolve a DAO wager
+    pub dao_quorum: u64,
     pub bump: u8,
 }
 
@@ -24,6 +41,22 @@ impl Platform {
         2 + // platform_fee_bps
         2 + // deployer_fee_bps
         8 + // wager_creation_fee
+        1 + // self_trade_behavior
+        8 + // resolution_bond
+        8 + // dispute_window
+        8 + // proposer_reward
+        8 + // resolution_grace_period
+        32 + // governance_mint
+        8 + // dao_voting_period
+        8 + // dao_quorum
         1 + // bump
         64; // padding
+    
+    /// The longest any resolver can hold a wager before the platform falls
+    /// back to resolving it; the grace period must outlast it
+    pub fn longest_fallback_delay(&self) -> i64 {
+        ORACLE_FALLBACK_DELAY
+            .max(ATTESTATION_FALLBACK_DELAY)
+            .max(self.dao_voting_period)
+    }
 }
\ No newline at end of file
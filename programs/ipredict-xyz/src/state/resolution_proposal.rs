@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::Resolution;
+
+/// A bonded resolution proposed for a wager. Holds the proposer's bond, and
+/// the disputer's matching bond once challenged, as lamports until the
+/// proposal is finalized or the platform authority rules on the dispute.
+#[account]
+pub struct ResolutionProposal {
+    pub wager: Pubkey,
+    pub proposer: Pubkey,
+    pub resolution: Resolution,
+    pub bond: u64,
+    pub proposed_at: i64,
+    pub dispute_deadline: i64,
+    /// `Pubkey::default()` until disputed
+    pub disputer: Pubkey,
+    pub bump: u8,
+}
+
+impl ResolutionProposal {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        32 + // proposer
+        1 + // resolution
+        8 + // bond
+        8 + // proposed_at
+        8 + // dispute_deadline
+        32 + // disputer
+        1 + // bump
+        32; // padding
+    
+    pub fn is_disputed(&self) -> bool {
+        self.disputer != Pubkey::default()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{Wager, Resolution, ResolutionArbitrator};
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+/// What a resolver relied on to settle a wager
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ResolutionEvidence {
+    pub source_uri: String,
+    /// Hash of the source content as the resolver saw it
+    pub content_hash: [u8; 32],
+    pub rationale: String,
+}
+
+impl ResolutionEvidence {
+    pub fn validate(&self) -> Result<()> {
+        if self.source_uri.len() > MAX_URI_LENGTH {
+            return Err(IpredictError::UriTooLong.into());
+        }
+        if self.rationale.len() > MAX_RATIONALE_LENGTH {
+            return Err(IpredictError::RationaleTooLong.into());
+        }
+        Ok(())
+    }
+}
+
+/// The published evidence behind a wager's resolution, one per wager so
+/// disputes, audits and indexers can find exactly what the resolver relied
+/// on. A proposal's record is replaced if the platform overturns it.
+#[account]
+pub struct ResolutionRecord {
+    pub wager: Pubkey,
+    pub resolver: Pubkey,
+    pub arbitrator: ResolutionArbitrator,
+    pub resolution: Resolution,
+    pub source_uri: String,
+    pub content_hash: [u8; 32],
+    pub rationale: String,
+    pub published_at: i64,
+    pub bump: u8,
+}
+
+impl ResolutionRecord {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        32 + // resolver
+        1 + // arbitrator
+        1 + // resolution
+        4 + MAX_URI_LENGTH + // source_uri
+        32 + // content_hash
+        4 + MAX_RATIONALE_LENGTH + // rationale
+        8 + // published_at
+        1 + // bump
+        32; // padding
+    
+    pub fn publish(
+        &mut self,
+        wager: &Account<Wager>,
+        resolver: Pubkey,
+        resolution: Resolution,
+        evidence: ResolutionEvidence,
+        published_at: i64,
+        bump: u8,
+    ) -> Result<()> {
+        evidence.validate()?;
+        
+        self.wager = wager.key();
+        self.resolver = resolver;
+        self.arbitrator = wager.resolution_arbitrator;
+        self.resolution = resolution;
+        self.source_uri = evidence.source_uri;
+        self.content_hash = evidence.content_hash;
+        self.rationale = evidence.rationale;
+        self.published_at = published_at;
+        self.bump = bump;
+        
+        msg!("Resolution record for wager {} published by {}", wager.wager_id, resolver);
+        
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use crate::state::Resolution;
+use crate::constants::*;
+use crate::errors::IpredictError;
+
+/// An M-of-N committee that resolves one wager. Each member's current vote
+/// sits at the member's index in `votes`; a pending member rotation, numbered
+/// by `rotation_nonce`, collects approvals and cancellations the same way, as
+/// bitmasks over member indexes.
+#[account]
+pub struct ResolverCommittee {
+    pub wager: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub vote_deadline: i64,
+    pub votes: Vec<Resolution>,
+    pub pending_members: Vec<Pubkey>,
+    pub pending_threshold: u8,
+    pub rotation_approvals: u16,
+    pub rotation_cancellations: u16,
+    pub rotation_nonce: u64,
+    pub rotation_expires_at: i64,
+    pub bump: u8,
+}
+
+impl ResolverCommittee {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        4 + 32 * MAX_COMMITTEE_MEMBERS + // members
+        1 + // threshold
+        8 + // vote_deadline
+        4 + MAX_COMMITTEE_MEMBERS + // votes
+        4 + 32 * MAX_COMMITTEE_MEMBERS + // pending_members
+        1 + // pending_threshold
+        2 + // rotation_approvals
+        2 + // rotation_cancellations
+        8 + // rotation_nonce
+        8 + // rotation_expires_at
+        1 + // bump
+        32; // padding
+    
+    /// Check a member set and threshold: MIN..=MAX members, no duplicates,
+    /// and a threshold of at least MIN_COMMITTEE_THRESHOLD but no larger than
+    /// the set, so no single key can resolve the wager alone
+    pub fn validate(members: &[Pubkey], threshold: u8) -> Result<()> {
+        let has_duplicates = members
+            .iter()
+            .enumerate()
+            .any(|(i, member)| members[..i].contains(member));
+        if members.len() < MIN_COMMITTEE_MEMBERS
+            || members.len() > MAX_COMMITTEE_MEMBERS
+            || has_duplicates
+            || threshold < MIN_COMMITTEE_THRESHOLD
+            || threshold as usize > members.len()
+        {
+            return Err(IpredictError::InvalidCommittee.into());
+        }
+        Ok(())
+    }
+    
+    pub fn member_index(&self, member: &Pubkey) -> Result<usize> {
+        self.members
+            .iter()
+            .position(|m| m == member)
+            .ok_or_else(|| IpredictError::NotCommitteeMember.into())
+    }
+    
+    /// Record a member's vote and return how many members now agree with it
+    pub fn vote(&mut self, index: usize, resolution: Resolution) -> u8 {
+        self.votes[index] = resolution;
+        self.votes.iter().filter(|vote| **vote == resolution).count() as u8
+    }
+    
+    /// Whether the rotation numbered `rotation_nonce` is still collecting
+    /// approvals
+    pub fn rotation_pending(&self, now: i64) -> bool {
+        !self.pending_members.is_empty() && now < self.rotation_expires_at
+    }
+    
+    /// Open the next rotation proposal, replacing one that has expired
+    pub fn propose_rotation(&mut self, members: Vec<Pubkey>, threshold: u8, expires_at: i64) {
+        self.clear_rotation();
+        self.pending_members = members;
+        self.pending_threshold = threshold;
+        self.rotation_nonce += 1;
+        self.rotation_expires_at = expires_at;
+    }
+    
+    /// Drop the pending rotation; its nonce is never reused
+    pub fn clear_rotation(&mut self) {
+        self.pending_members = Vec::new();
+        self.pending_threshold = 0;
+        self.rotation_approvals = 0;
+        self.rotation_cancellations = 0;
+        self.rotation_expires_at = 0;
+    }
+    
+    /// Replace the members and threshold, clearing every vote cast so far
+    pub fn rotate(&mut self, members: Vec<Pubkey>, threshold: u8) {
+        self.votes = vec![Resolution::Pending; members.len()];
+        self.members = members;
+        self.threshold = threshold;
+        self.clear_rotation();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use crate::state::Resolution;
+
+/// One holder's governance tokens locked behind an outcome of a DAO-resolved
+/// wager. The tokens sit in the wager's DAO vault until the vote is over.
+#[account]
+pub struct VoteRecord {
+    pub wager: Pubkey,
+    pub voter: Pubkey,
+    pub resolution: Resolution,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // wager
+        32 + // voter
+        1 + // resolution
+        8 + // amount
+        1 + // bump
+        32; // padding
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use crate::state::{WagerStatus, Resolution, ResolutionArbitrator};
+use crate::state::{WagerStatus, Resolution, ResolutionArbitrator, OracleCondition, TokenType};
 use crate::constants::*;
+use anchor_spl::associated_token::get_associated_token_address;
 
 #[account]
 pub struct Wager {
@@ -11,12 +12,46 @@ pub struct Wager {
     pub no_mint: Pubkey,
     pub vault: Pubkey,
     pub order_book: Pubkey,
+    pub bid_escrow: Pubkey,
+    /// SPL mint the wager is collateralized in; `Pubkey::default()` for native SOL
+    pub collateral_mint: Pubkey,
+    /// Where the bid escrow's collateral is held: the bid escrow itself for
+    /// native SOL, otherwise a token account owned by the wager
+    pub bid_escrow_vault: Pubkey,
+    /// Collateral base units backing one YES + NO pair of token base units
+    pub collateral_per_set: u64,
+    /// Decimals of both outcome mints
+    pub token_decimals: u8,
     pub opening_time: i64,
     pub closing_time: i64,
     pub resolution_time: i64,
     pub status: WagerStatus,
     pub resolution: Resolution,
     pub resolution_arbitrator: ResolutionArbitrator,
+    /// Lamports held in this account to reward a correct resolution proposal
+    pub resolution_reward: u64,
+    /// Seconds after `resolution_time` before anyone can void the wager,
+    /// fixed from the platform's grace period at creation
+    pub resolution_grace_period: i64,
+    /// Price condition for wagers settled by `resolve_from_oracle`
+    pub oracle_condition: Option<OracleCondition>,
+    /// `ResolverCommittee` voting on this wager; `Pubkey::default()` if none
+    pub committee: Pubkey,
+    /// Key whose signed attestations resolve an AI-arbitrated wager
+    pub attester: Pubkey,
+    /// Token a DAO wager's voters lock, fixed from the platform at creation
+    pub governance_mint: Pubkey,
+    /// When a DAO wager's votes stop being accepted
+    pub dao_voting_end: i64,
+    /// Locked governance tokens a DAO wager's vote needs in total
+    pub dao_quorum: u64,
+    /// When the resolver halted trading; 0 while trading normally
+    pub halted_at: i64,
+    /// When an early-resolved wager's event actually happened; trades after
+    /// it were made at a known outcome. 0 unless resolved early.
+    pub event_time: i64,
+    /// When an order last filled; 0 if the wager never traded
+    pub last_fill_at: i64,
     pub total_yes_tokens: u64,
     pub total_no_tokens: u64,
     pub total_sol_deposited: u64,
@@ -24,6 +59,7 @@ pub struct Wager {
     pub total_fees_collected: u64,
     pub wager_id: u64,
     pub bump: u8,
+    pub vault_bump: u8,
 }
 
 impl Wager {
@@ -35,12 +71,28 @@ impl Wager {
         32 + // no_mint
         32 + // vault
         32 + // order_book
+        32 + // bid_escrow
+        32 + // collateral_mint
+        32 + // bid_escrow_vault
+        8 + // collateral_per_set
+        1 + // token_decimals
         8 + // opening_time
         8 + // closing_time
         8 + // resolution_time
         1 + // status
         1 + // resolution
         1 + // resolution_arbitrator
+        8 + // resolution_reward
+        8 + // resolution_grace_period
+        1 + OracleCondition::SIZE + // oracle_condition
+        32 + // committee
+        32 + // attester
+        32 + // governance_mint
+        8 + // dao_voting_end
+        8 + // dao_quorum
+        8 + // halted_at
+        8 + // event_time
+        8 + // last_fill_at
         8 + // total_yes_tokens
         8 + // total_no_tokens
         8 + // total_sol_deposited
@@ -48,16 +100,199 @@ impl Wager {
         8 + // total_fees_collected
         8 + // wager_id
         1 + // bump
+        1 + // vault_bump
         128; // padding
     
+    /// Whether the wager has gone live. A wager created ahead of its opening
+    /// time stays `Created` and counts as active once that time passes.
+    pub fn is_active(&self, clock: &Clock) -> bool {
+        match self.status {
+            WagerStatus::Active => true,
+            WagerStatus::Created => clock.unix_timestamp >= self.opening_time,
+            _ => false,
+        }
+    }
+    
     pub fn is_open(&self, clock: &Clock) -> bool {
-        self.status == WagerStatus::Active && 
+        self.is_active(clock) && 
+        !self.is_halted() && 
         clock.unix_timestamp >= self.opening_time && 
         clock.unix_timestamp < self.closing_time
     }
     
     pub fn is_resolvable(&self, clock: &Clock) -> bool {
-        self.status == WagerStatus::Active && 
+        self.is_active(clock) && 
         clock.unix_timestamp >= self.resolution_time
     }
+    
+    /// Whether the resolver can still be replaced by a committee: only before
+    /// trading opens and before anything has traded
+    pub fn accepts_committee(&self, clock: &Clock) -> bool {
+        self.status == WagerStatus::Created && 
+        clock.unix_timestamp < self.opening_time && 
+        self.total_volume_traded == 0
+    }
+    
+    pub fn is_halted(&self) -> bool {
+        self.halted_at != 0
+    }
+    
+    /// When anyone may void the wager if it is still unresolved
+    pub fn void_time(&self) -> i64 {
+        self.resolution_time.saturating_add(self.resolution_grace_period)
+    }
+    
+    
+    /// When the platform may resolve an oracle wager whose feed never gave a
+    /// usable price
+    pub fn oracle_fallback_time(&self) -> i64 {
+        self.resolution_time.saturating_add(ORACLE_FALLBACK_DELAY)
+    }
+    
+    /// When the platform may resolve an AI wager its attester never settled
+    pub fn attestation_fallback_time(&self) -> i64 {
+        self.resolution_time.saturating_add(ATTESTATION_FALLBACK_DELAY)
+    }
+    
+    pub fn outcome_mint(&self, token_type: TokenType) -> Pubkey {
+        match token_type {
+            TokenType::Yes => self.yes_mint,
+            TokenType::No => self.no_mint,
+        }
+    }
+    
+    pub fn is_native_collateral(&self) -> bool {
+        self.collateral_mint == Pubkey::default()
+    }
+    
+    /// Where `owner` sends and receives this wager's collateral: their wallet
+    /// for native SOL, or their associated token account for an SPL mint
+    pub fn collateral_account(&self, owner: &Pubkey) -> Pubkey {
+        if self.is_native_collateral() {
+            *owner
+        } else {
+            get_associated_token_address(owner, &self.collateral_mint)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ResolverCommittee;
+    
+    const OPENING_TIME: i64 = 1_700_000_000;
+    const CLOSING_TIME: i64 = OPENING_TIME + 86_400;
+    const RESOLUTION_TIME: i64 = CLOSING_TIME + 3_600;
+    
+    fn at(unix_timestamp: i64) -> Clock {
+        Clock { unix_timestamp, ..Default::default() }
+    }
+    
+    /// A wager as `create_wager` leaves it when created ahead of its opening time
+    fn created_wager() -> Wager {
+        Wager {
+            creator: Pubkey::new_unique(),
+            name: String::new(),
+            description: String::new(),
+            yes_mint: Pubkey::new_unique(),
+            no_mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            order_book: Pubkey::new_unique(),
+            bid_escrow: Pubkey::new_unique(),
+            collateral_mint: Pubkey::default(),
+            bid_escrow_vault: Pubkey::new_unique(),
+            collateral_per_set: 1_000_000,
+            token_decimals: 0,
+            opening_time: OPENING_TIME,
+            closing_time: CLOSING_TIME,
+            resolution_time: RESOLUTION_TIME,
+            status: WagerStatus::Created,
+            resolution: Resolution::Pending,
+            resolution_arbitrator: ResolutionArbitrator::Platform,
+            resolution_reward: 0,
+            resolution_grace_period: DEFAULT_RESOLUTION_GRACE_PERIOD,
+            oracle_condition: None,
+            committee: Pubkey::default(),
+            attester: Pubkey::default(),
+            governance_mint: Pubkey::default(),
+            dao_voting_end: 0,
+            dao_quorum: 0,
+            halted_at: 0,
+            event_time: 0,
+            last_fill_at: 0,
+            total_yes_tokens: 0,
+            total_no_tokens: 0,
+            total_sol_deposited: 0,
+            total_volume_traded: 0,
+            total_fees_collected: 0,
+            wager_id: 1,
+            bump: 255,
+            vault_bump: 255,
+        }
+    }
+    
+    #[test]
+    fn created_wager_goes_live_at_opening_time() {
+        let wager = created_wager();
+        assert!(!wager.is_active(&at(OPENING_TIME - 1)));
+        assert!(!wager.is_open(&at(OPENING_TIME - 1)));
+        assert!(wager.is_open(&at(OPENING_TIME)));
+        assert!(!wager.is_open(&at(CLOSING_TIME)));
+        assert!(!wager.is_resolvable(&at(RESOLUTION_TIME - 1)));
+        assert!(wager.is_resolvable(&at(RESOLUTION_TIME)));
+    }
+    
+    #[test]
+    fn committee_wager_trades_and_resolves_by_vote() {
+        let mut wager = created_wager();
+        
+        // Attach a 2-of-3 committee before trading opens
+        let members = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        ResolverCommittee::validate(&members, 2).unwrap();
+        assert!(wager.accepts_committee(&at(OPENING_TIME - 1)));
+        let mut committee = ResolverCommittee {
+            wager: Pubkey::new_unique(),
+            votes: vec![Resolution::Pending; members.len()],
+            members,
+            threshold: 2,
+            vote_deadline: RESOLUTION_TIME + 86_400,
+            pending_members: Vec::new(),
+            pending_threshold: 0,
+            rotation_approvals: 0,
+            rotation_cancellations: 0,
+            rotation_nonce: 0,
+            rotation_expires_at: 0,
+            bump: 255,
+        };
+        wager.resolution_arbitrator = ResolutionArbitrator::Committee;
+        
+        // Trade once it opens; no committee can be swapped in after that
+        let now = at(OPENING_TIME + 60);
+        assert!(wager.is_open(&now));
+        wager.total_volume_traded += 500_000;
+        wager.last_fill_at = now.unix_timestamp;
+        assert!(!wager.accepts_committee(&now));
+        
+        // Members vote once the resolution time passes
+        let now = at(RESOLUTION_TIME);
+        assert!(wager.is_resolvable(&now));
+        assert!(now.unix_timestamp < committee.vote_deadline);
+        let first = committee.member_index(&committee.members[0]).unwrap();
+        let second = committee.member_index(&committee.members[2]).unwrap();
+        assert_eq!(committee.vote(first, Resolution::YesWon), 1);
+        assert_eq!(committee.vote(second, Resolution::YesWon), committee.threshold);
+    }
+    
+    #[test]
+    fn rejects_committee_once_trading_opens() {
+        let mut wager = created_wager();
+        assert!(!wager.accepts_committee(&at(OPENING_TIME)));
+        wager.total_volume_traded = 1;
+        assert!(!wager.accepts_committee(&at(OPENING_TIME - 1)));
+        
+        let mut wager = created_wager();
+        wager.status = WagerStatus::Active;
+        assert!(!wager.accepts_committee(&at(OPENING_TIME - 1)));
+    }
 }
\ No newline at end of file
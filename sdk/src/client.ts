@@ -10,6 +10,7 @@ import {
 } from '@solana/web3.js';
 import {
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   createAssociatedTokenAccountInstruction,
@@ -21,6 +22,7 @@ import {
   Wager,
   OrderBook,
   UserPosition,
+  OpenOrders,
   CreateWagerParams,
   PlaceOrderParams,
   QuickBuyParams,
@@ -33,14 +35,19 @@ import {
   getPlatformPDA,
   getWagerPDA,
   getVaultPDA,
-  getOrderBookPDA,
   getUserPositionPDA,
   getYesMintPDA,
   getNoMintPDA,
   getEscrowPDA,
+  getBidEscrowPDA,
+  getOpenOrdersPDA,
+  getCollateralVaultPDA,
+  getBidEscrowVaultPDA,
+  getCollateralAccount,
+  getMakerAccounts,
   confirmTransaction,
 } from './utils';
-import { PROGRAM_ID } from './constants';
+import { PROGRAM_ID, ORDER_BOOK_SIZE } from './constants';
 import IDL from './idl/ipredict_xyz.json';
 
 export class IpredictClient {
@@ -92,32 +99,68 @@ export class IpredictClient {
     const [wagerPDA] = await getWagerPDA(wagerId);
     const [yesMintPDA] = await getYesMintPDA(wagerPDA);
     const [noMintPDA] = await getNoMintPDA(wagerPDA);
-    const [orderBookPDA] = await getOrderBookPDA(wagerPDA);
+    const [yesEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.Yes);
+    const [noEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.No);
+    const [bidEscrowPDA] = await getBidEscrowPDA(wagerPDA);
     const [vaultPDA] = await getVaultPDA(wagerPDA);
     const [platformPDA] = await getPlatformPDA();
 
+    // SPL wagers hold their collateral in token accounts owned by the wager
+    const collateralMint = params.collateralMint ?? null;
+    const collateralVault = collateralMint ? (await getCollateralVaultPDA(wagerPDA))[0] : null;
+    const bidEscrowVault = collateralMint ? (await getBidEscrowVaultPDA(wagerPDA))[0] : null;
+
+    // Outcome mints carry metadata only under Token-2022
+    const metadataUri = params.metadataUri ?? '';
+    const tokenProgram = metadataUri ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+
+    // The order book is too large for `init`, so it is allocated here
+    const orderBook = Keypair.generate();
+    const createOrderBookIx = SystemProgram.createAccount({
+      fromPubkey: this.wallet.publicKey,
+      newAccountPubkey: orderBook.publicKey,
+      lamports: await this.connection.getMinimumBalanceForRentExemption(ORDER_BOOK_SIZE),
+      space: ORDER_BOOK_SIZE,
+      programId: PROGRAM_ID,
+    });
+
     const tx = await this.program.methods
       .createWager(
         params.name,
         params.description,
         params.openingTime,
         params.closingTime,
-        params.resolutionTime
+        params.resolutionTime,
+        metadataUri,
+        params.collateralPerSet,
+        params.tokenDecimals,
+        params.resolutionArbitrator,
+        params.oracleCondition ?? null,
+        params.attester ?? null
       )
       .accounts({
         platform: platformPDA,
         wager: wagerPDA,
         yesMint: yesMintPDA,
         noMint: noMintPDA,
-        orderBook: orderBookPDA,
+        orderBook: orderBook.publicKey,
+        yesEscrow: yesEscrowPDA,
+        noEscrow: noEscrowPDA,
+        bidEscrow: bidEscrowPDA,
         vault: vaultPDA,
+        collateralMint,
+        collateralVault,
+        bidEscrowVault,
         creator: this.wallet.publicKey,
         feeRecipient: platform.feeRecipient,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         rent: SYSVAR_RENT_PUBKEY,
       })
+      .preInstructions([createOrderBookIx])
+      .signers([orderBook])
       .rpc();
 
     return tx;
@@ -138,32 +181,37 @@ export class IpredictClient {
     const wager = await this.getWager(wagerId);
     if (!wager) throw new Error('Wager not found');
 
-    const [yesMintPDA] = await getYesMintPDA(wagerPDA);
-    const [noMintPDA] = await getNoMintPDA(wagerPDA);
-    const [vaultPDA] = await getVaultPDA(wagerPDA);
     const [userPositionPDA] = await getUserPositionPDA(this.wallet.publicKey, wagerPDA);
+    const tokenProgram = await this.getOutcomeTokenProgram(wager);
 
     const userYesAccount = await getAssociatedTokenAddress(
-      yesMintPDA,
-      this.wallet.publicKey
+      wager.yesMint,
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
     const userNoAccount = await getAssociatedTokenAddress(
-      noMintPDA,
-      this.wallet.publicKey
+      wager.noMint,
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
 
+    // `amount` is in the wager's collateral base units: lamports for native SOL
     const tx = await this.program.methods
       .depositAndMint(amount)
       .accounts({
         wager: wagerPDA,
-        yesMint: yesMintPDA,
-        noMint: noMintPDA,
+        yesMint: wager.yesMint,
+        noMint: wager.noMint,
         userYesAccount,
         userNoAccount,
         userPosition: userPositionPDA,
-        vault: vaultPDA,
+        vault: wager.vault,
         user: this.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        userCollateral: await getCollateralAccount(wager.collateralMint, this.wallet.publicKey),
+        tokenProgram,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -172,61 +220,129 @@ export class IpredictClient {
     return tx;
   }
 
+  async initOpenOrders(wagerId: BN): Promise<string> {
+    const [wagerPDA] = await getWagerPDA(wagerId);
+    const [openOrdersPDA] = await getOpenOrdersPDA(wagerPDA, this.wallet.publicKey);
+
+    const tx = await this.program.methods
+      .initOpenOrders()
+      .accounts({
+        wager: wagerPDA,
+        openOrders: openOrdersPDA,
+        user: this.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    return tx;
+  }
+
+  async getOpenOrders(wagerId: BN, user?: PublicKey): Promise<OpenOrders | null> {
+    const userPubkey = user || this.wallet.publicKey;
+    const [wagerPDA] = await getWagerPDA(wagerId);
+    const [openOrdersPDA] = await getOpenOrdersPDA(wagerPDA, userPubkey);
+    try {
+      const openOrders = await (this.program.account as any).openOrders.fetch(openOrdersPDA);
+      return openOrders as OpenOrders;
+    } catch {
+      return null;
+    }
+  }
+
   async placeOrder(params: PlaceOrderParams): Promise<string> {
+    const [platformPDA] = await getPlatformPDA();
+    const platform = await this.getPlatform();
+    if (!platform) throw new Error('Platform not initialized');
+
     const [wagerPDA] = await getWagerPDA(params.wagerId);
     const wager = await this.getWager(params.wagerId);
     if (!wager) throw new Error('Wager not found');
 
-    const [orderBookPDA] = await getOrderBookPDA(wagerPDA);
     const [userPositionPDA] = await getUserPositionPDA(this.wallet.publicKey, wagerPDA);
-    const [escrowPDA] = await getEscrowPDA(wagerPDA, this.wallet.publicKey);
+    const [openOrdersPDA] = await getOpenOrdersPDA(wagerPDA, this.wallet.publicKey);
+    const [yesEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.Yes);
+    const [noEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.No);
+    const tokenProgram = await this.getOutcomeTokenProgram(wager);
 
     const mint = params.tokenType === TokenType.Yes ? wager.yesMint : wager.noMint;
     const userTokenAccount = await getAssociatedTokenAddress(
       mint,
-      this.wallet.publicKey
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
 
     const tx = await this.program.methods
-      .placeOrder(params.side, params.tokenType, params.price, params.quantity)
+      .placeOrder(
+        params.side,
+        params.tokenType,
+        params.orderType,
+        params.price,
+        params.quantity,
+        params.expiresAt ?? null,
+        params.selfTradeBehavior ?? null
+      )
       .accounts({
+        platform: platformPDA,
         wager: wagerPDA,
-        orderBook: orderBookPDA,
+        orderBook: wager.orderBook,
         userPosition: userPositionPDA,
+        openOrders: openOrdersPDA,
         userTokenAccount,
-        escrowAccount: escrowPDA,
+        yesMint: wager.yesMint,
+        noMint: wager.noMint,
+        yesEscrow: yesEscrowPDA,
+        noEscrow: noEscrowPDA,
+        bidEscrow: wager.bidEscrow,
+        bidEscrowVault: wager.bidEscrowVault,
+        vault: wager.vault,
         user: this.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        platformFeeRecipient: await getCollateralAccount(wager.collateralMint, platform.feeRecipient),
+        creatorFeeRecipient: await getCollateralAccount(wager.collateralMint, wager.creator),
+        tokenProgram,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
       })
+      .remainingAccounts(await getMakerAccounts(wagerPDA, params.makers ?? []))
       .rpc();
 
     return tx;
   }
 
-  async cancelOrder(wagerId: BN, orderId: BN): Promise<string> {
+  async cancelOrder(wagerId: BN, orderId: BN, tokenType: TokenType = TokenType.Yes): Promise<string> {
     const [wagerPDA] = await getWagerPDA(wagerId);
     const wager = await this.getWager(wagerId);
     if (!wager) throw new Error('Wager not found');
 
-    const [orderBookPDA] = await getOrderBookPDA(wagerPDA);
-    const [escrowPDA] = await getEscrowPDA(wagerPDA, this.wallet.publicKey);
-    
-    // This is simplified - in reality we'd need to determine the token type
+    const [openOrdersPDA] = await getOpenOrdersPDA(wagerPDA, this.wallet.publicKey);
+    const [yesEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.Yes);
+    const [noEscrowPDA] = await getEscrowPDA(wagerPDA, TokenType.No);
+    const tokenProgram = await this.getOutcomeTokenProgram(wager);
+
+    // Receives the escrowed tokens of a sell order in `tokenType`
+    const mint = tokenType === TokenType.Yes ? wager.yesMint : wager.noMint;
     const userTokenAccount = await getAssociatedTokenAddress(
-      wager.yesMint,
-      this.wallet.publicKey
+      mint,
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
 
     const tx = await this.program.methods
       .cancelOrder(orderId)
       .accounts({
         wager: wagerPDA,
-        orderBook: orderBookPDA,
+        orderBook: wager.orderBook,
+        openOrders: openOrdersPDA,
         userTokenAccount,
-        escrowAccount: escrowPDA,
+        yesEscrow: yesEscrowPDA,
+        noEscrow: noEscrowPDA,
+        yesMint: wager.yesMint,
+        noMint: wager.noMint,
         user: this.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgram,
       })
       .rpc();
 
@@ -242,13 +358,16 @@ export class IpredictClient {
     const wager = await this.getWager(params.wagerId);
     if (!wager) throw new Error('Wager not found');
 
-    const [orderBookPDA] = await getOrderBookPDA(wagerPDA);
     const [userPositionPDA] = await getUserPositionPDA(this.wallet.publicKey, wagerPDA);
+    const [escrowPDA] = await getEscrowPDA(wagerPDA, params.tokenType);
+    const tokenProgram = await this.getOutcomeTokenProgram(wager);
 
     const mint = params.tokenType === TokenType.Yes ? wager.yesMint : wager.noMint;
     const userTokenAccount = await getAssociatedTokenAddress(
       mint,
-      this.wallet.publicKey
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
 
     const tx = await this.program.methods
@@ -256,15 +375,22 @@ export class IpredictClient {
       .accounts({
         platform: platformPDA,
         wager: wagerPDA,
-        orderBook: orderBookPDA,
+        orderBook: wager.orderBook,
         userPosition: userPositionPDA,
         userTokenAccount,
+        escrowAccount: escrowPDA,
+        tokenMint: mint,
+        bidEscrow: wager.bidEscrow,
+        bidEscrowVault: wager.bidEscrowVault,
         user: this.wallet.publicKey,
-        platformFeeRecipient: platform.feeRecipient,
-        creatorFeeRecipient: wager.creator,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        platformFeeRecipient: await getCollateralAccount(wager.collateralMint, platform.feeRecipient),
+        creatorFeeRecipient: await getCollateralAccount(wager.collateralMint, wager.creator),
+        tokenProgram,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(await getMakerAccounts(wagerPDA, params.makers))
       .rpc();
 
     return tx;
@@ -291,18 +417,20 @@ export class IpredictClient {
     const wager = await this.getWager(wagerId);
     if (!wager) throw new Error('Wager not found');
 
-    const [yesMintPDA] = await getYesMintPDA(wagerPDA);
-    const [noMintPDA] = await getNoMintPDA(wagerPDA);
-    const [vaultPDA] = await getVaultPDA(wagerPDA);
     const [userPositionPDA] = await getUserPositionPDA(this.wallet.publicKey, wagerPDA);
+    const tokenProgram = await this.getOutcomeTokenProgram(wager);
 
     const userYesAccount = await getAssociatedTokenAddress(
-      yesMintPDA,
-      this.wallet.publicKey
+      wager.yesMint,
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
     const userNoAccount = await getAssociatedTokenAddress(
-      noMintPDA,
-      this.wallet.publicKey
+      wager.noMint,
+      this.wallet.publicKey,
+      false,
+      tokenProgram
     );
 
     const tx = await this.program.methods
@@ -312,11 +440,13 @@ export class IpredictClient {
         userPosition: userPositionPDA,
         userYesAccount,
         userNoAccount,
-        yesMint: yesMintPDA,
-        noMint: noMintPDA,
-        vault: vaultPDA,
+        yesMint: wager.yesMint,
+        noMint: wager.noMint,
+        vault: wager.vault,
         user: this.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        userCollateral: await getCollateralAccount(wager.collateralMint, this.wallet.publicKey),
+        tokenProgram,
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -325,10 +455,10 @@ export class IpredictClient {
   }
 
   async getOrderBook(wagerId: BN): Promise<OrderBook | null> {
-    const [wagerPDA] = await getWagerPDA(wagerId);
-    const [orderBookPDA] = await getOrderBookPDA(wagerPDA);
+    const wager = await this.getWager(wagerId);
+    if (!wager) return null;
     try {
-      const orderBook = await (this.program.account as any).orderBook.fetch(orderBookPDA);
+      const orderBook = await (this.program.account as any).orderBook.fetch(wager.orderBook);
       return orderBook as OrderBook;
     } catch {
       return null;
@@ -370,27 +500,41 @@ export class IpredictClient {
   }
 
   /**
-   * Match compatible orders in the order book
+   * Match compatible orders in the order book. `makers` are the owners of
+   * every order that may fill; each needs an open orders account.
    */
-  async matchOrders(wagerId: BN, maxIterations: number = 10): Promise<string> {
-    const [wager] = getWagerPDA(wagerId);
-    const [platform] = getPlatformPDA();
-    const [orderBook] = getOrderBookPDA(wagerId);
-    const [yesEscrow] = getEscrowPDA(wagerId, TokenType.Yes);
-    const [noEscrow] = getEscrowPDA(wagerId, TokenType.No);
+  async matchOrders(wagerId: BN, makers: PublicKey[], maxIterations: number = 10): Promise<string> {
+    const [platformPDA] = await getPlatformPDA();
+    const platform = await this.getPlatform();
+    if (!platform) throw new Error('Platform not initialized');
+
+    const [wagerPDA] = await getWagerPDA(wagerId);
+    const wager = await this.getWager(wagerId);
+    if (!wager) throw new Error('Wager not found');
+
+    const [yesEscrow] = await getEscrowPDA(wagerPDA, TokenType.Yes);
+    const [noEscrow] = await getEscrowPDA(wagerPDA, TokenType.No);
 
     const tx = await this.program.methods
       .matchOrders(maxIterations)
       .accounts({
-        platform,
-        wager,
-        orderBook,
+        platform: platformPDA,
+        wager: wagerPDA,
+        orderBook: wager.orderBook,
+        yesMint: wager.yesMint,
+        noMint: wager.noMint,
         yesEscrow,
         noEscrow,
-        matcher: this.wallet.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        bidEscrow: wager.bidEscrow,
+        bidEscrowVault: wager.bidEscrowVault,
+        vault: wager.vault,
+        platformFeeRecipient: await getCollateralAccount(wager.collateralMint, platform.feeRecipient),
+        creatorFeeRecipient: await getCollateralAccount(wager.collateralMint, wager.creator),
+        tokenProgram: await this.getOutcomeTokenProgram(wager),
+        collateralTokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      .remainingAccounts(await getMakerAccounts(wagerPDA, makers))
       .transaction();
 
     const signature = await this.provider.sendAndConfirm(tx);
@@ -9,6 +9,11 @@ export const VAULT_SEED = 'vault';
 export const ORDER_BOOK_SEED = 'order_book';
 export const ORDER_SEED = 'order';
 export const USER_POSITION_SEED = 'user_position';
+export const ESCROW_SEED = 'escrow';
+export const BID_ESCROW_SEED = 'bid_escrow';
+export const OPEN_ORDERS_SEED = 'open_orders';
+export const COLLATERAL_VAULT_SEED = 'collateral_vault';
+export const BID_ESCROW_VAULT_SEED = 'bid_escrow_vault';
 
 export const TOKENS_PER_SOL = new BN(100);
 export const LAMPORTS_PER_TOKEN = new BN(10_000_000); // 0.01 SOL
@@ -21,5 +26,10 @@ export const BPS_DIVISOR = new BN(10_000);
 export const MAX_NAME_LENGTH = 200;
 export const MAX_DESCRIPTION_LENGTH = 1000;
 export const MAX_ORDERS_PER_BOOK = 1000;
+export const MAX_URI_LENGTH = 200;
+export const MAX_TOKEN_DECIMALS = 9;
+
+// Byte sizes of accounts the client allocates itself
+export const ORDER_BOOK_SIZE = 400_112;
 
 export const WAGER_CREATION_FEE = new BN(1_000_000_000); // 1 SOL
\ No newline at end of file
@@ -28,6 +28,36 @@ export enum ResolutionArbitrator {
   Platform = 'Platform',
   AI = 'AI',
   DAO = 'DAO',
+  Oracle = 'Oracle',
+  Committee = 'Committee',
+}
+
+export enum OrderType {
+  Limit = 'Limit',
+  PostOnly = 'PostOnly',
+  ImmediateOrCancel = 'ImmediateOrCancel',
+  FillOrKill = 'FillOrKill',
+}
+
+export enum SelfTradeBehavior {
+  CancelNewest = 'CancelNewest',
+  CancelOldest = 'CancelOldest',
+  CancelBoth = 'CancelBoth',
+  DecrementAndCancel = 'DecrementAndCancel',
+}
+
+export enum OracleComparator {
+  GreaterThan = 'GreaterThan',
+  LessThan = 'LessThan',
+  Between = 'Between',
+}
+
+export interface OracleCondition {
+  priceAccount: PublicKey;
+  comparator: OracleComparator;
+  strike: BN;
+  upperStrike: BN;
+  exponent: number;
 }
 
 export interface Platform {
@@ -50,12 +80,19 @@ export interface Wager {
   noMint: PublicKey;
   vault: PublicKey;
   orderBook: PublicKey;
+  bidEscrow: PublicKey;
+  collateralMint: PublicKey;
+  bidEscrowVault: PublicKey;
+  collateralPerSet: BN;
+  tokenDecimals: number;
   openingTime: BN;
   closingTime: BN;
   resolutionTime: BN;
   status: WagerStatus;
   resolution: Resolution;
   resolutionArbitrator: ResolutionArbitrator;
+  oracleCondition: OracleCondition | null;
+  attester: PublicKey;
   totalYesTokens: BN;
   totalNoTokens: BN;
   totalSolDeposited: BN;
@@ -74,6 +111,8 @@ export interface Order {
   quantity: BN;
   filledQuantity: BN;
   timestamp: BN;
+  expiresAt: BN;
+  selfTradeBehavior: SelfTradeBehavior;
 }
 
 export interface OrderBook {
@@ -99,20 +138,41 @@ export interface UserPosition {
   bump: number;
 }
 
+export interface OpenOrders {
+  owner: PublicKey;
+  wager: PublicKey;
+  orderIds: BN[];
+  solFree: BN;
+  yesTokensFree: BN;
+  noTokensFree: BN;
+  bump: number;
+}
+
 export interface CreateWagerParams {
   name: string;
   description: string;
   openingTime: BN;
   closingTime: BN;
   resolutionTime: BN;
+  metadataUri?: string; // non-empty to create Token-2022 mints with metadata
+  collateralMint?: PublicKey; // omit for native SOL
+  collateralPerSet: BN;
+  tokenDecimals: number;
+  resolutionArbitrator: ResolutionArbitrator;
+  oracleCondition?: OracleCondition; // Oracle wagers only
+  attester?: PublicKey; // AI wagers only
 }
 
 export interface PlaceOrderParams {
   wagerId: BN;
   side: OrderSide;
   tokenType: TokenType;
+  orderType: OrderType;
   price: BN;
   quantity: BN;
+  expiresAt?: BN; // omit for good til cancelled
+  selfTradeBehavior?: SelfTradeBehavior; // defaults to CancelNewest
+  makers?: PublicKey[]; // owners of resting orders this one may fill
 }
 
 export interface QuickBuyParams {
@@ -120,4 +180,5 @@ export interface QuickBuyParams {
   tokenType: TokenType;
   solAmount: BN;
   minTokensOut: BN;
+  makers: PublicKey[]; // owners of the sell orders this buy may fill
 }
\ No newline at end of file
@@ -1,12 +1,18 @@
 import { PublicKey, Connection, Commitment } from '@solana/web3.js';
 import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from '@solana/spl-token';
 import BN from 'bn.js';
+import { TokenType } from './types';
 import {
   PLATFORM_SEED,
   WAGER_SEED,
   VAULT_SEED,
   ORDER_BOOK_SEED,
   USER_POSITION_SEED,
+  ESCROW_SEED,
+  BID_ESCROW_SEED,
+  OPEN_ORDERS_SEED,
+  COLLATERAL_VAULT_SEED,
+  BID_ESCROW_VAULT_SEED,
   PROGRAM_ID,
   LAMPORTS_PER_TOKEN,
   TOKENS_PER_SOL,
@@ -66,15 +72,73 @@ export const getNoMintPDA = async (wager: PublicKey): Promise<[PublicKey, number
 };
 
 export const getEscrowPDA = async (
+  wager: PublicKey,
+  tokenType: TokenType
+): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [Buffer.from(ESCROW_SEED), wager.toBuffer(), Buffer.from(tokenType === TokenType.Yes ? 'yes' : 'no')],
+    PROGRAM_ID
+  );
+};
+
+export const getBidEscrowPDA = async (wager: PublicKey): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [Buffer.from(BID_ESCROW_SEED), wager.toBuffer()],
+    PROGRAM_ID
+  );
+};
+
+export const getOpenOrdersPDA = async (
   wager: PublicKey,
   user: PublicKey
 ): Promise<[PublicKey, number]> => {
   return PublicKey.findProgramAddress(
-    [Buffer.from('escrow'), wager.toBuffer(), user.toBuffer()],
+    [Buffer.from(OPEN_ORDERS_SEED), wager.toBuffer(), user.toBuffer()],
+    PROGRAM_ID
+  );
+};
+
+export const getCollateralVaultPDA = async (wager: PublicKey): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [Buffer.from(COLLATERAL_VAULT_SEED), wager.toBuffer()],
+    PROGRAM_ID
+  );
+};
+
+export const getBidEscrowVaultPDA = async (wager: PublicKey): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [Buffer.from(BID_ESCROW_VAULT_SEED), wager.toBuffer()],
     PROGRAM_ID
   );
 };
 
+// Where `owner` receives a wager's collateral: the wallet itself for native
+// SOL, otherwise its associated token account for the collateral mint
+export const getCollateralAccount = async (
+  collateralMint: PublicKey,
+  owner: PublicKey
+): Promise<PublicKey> => {
+  if (collateralMint.equals(PublicKey.default)) {
+    return owner;
+  }
+  return getAssociatedTokenAddress(collateralMint, owner, true);
+};
+
+// Writable open orders accounts of the makers a trade may fill, passed as
+// remaining accounts
+export const getMakerAccounts = async (
+  wager: PublicKey,
+  makers: PublicKey[]
+): Promise<{ pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[]> => {
+  const unique = makers.filter((maker, i) => makers.findIndex(m => m.equals(maker)) === i);
+  return Promise.all(
+    unique.map(async maker => {
+      const [openOrders] = await getOpenOrdersPDA(wager, maker);
+      return { pubkey: openOrders, isSigner: false, isWritable: true };
+    })
+  );
+};
+
 export const solToLamports = (sol: number): BN => {
   return new BN(sol * LAMPORTS_PER_SOL.toNumber());
 };